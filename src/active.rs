@@ -0,0 +1,93 @@
+/// How many cells wide & tall each tile is. This has to be at least as big as the
+/// biggest neighborhood radius, so a change can only reach the tiles right next to it
+pub const TILE_SIZE: usize = 16;
+
+/// The cells that changed in a generation, and which tiles of the board they're in.
+///
+/// A cell can only change if something in its neighborhood (or the cell itself)
+/// changed in the generation before, so only the tiles near these changes need
+/// to be worked out next time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changes {
+    /// The indexes of the cells that changed, row by row
+    pub cells: Vec<usize>,
+    tiles: Vec<bool>,
+    /// Whether anything changed close enough to an edge to reach past it
+    near_edge: bool,
+    width: usize,
+    height: usize,
+    radius: usize,
+}
+impl Changes {
+    /// No changes on a board of the given size, w/ a rule that reaches 'radius' cells
+    pub fn new(width: usize, height: usize, radius: usize) -> Self {
+        Changes {
+            cells: Vec::new(),
+            tiles: vec![false; width.div_ceil(TILE_SIZE) * height.div_ceil(TILE_SIZE)],
+            near_edge: false,
+            width,
+            height,
+            radius,
+        }
+    }
+    /// The tile holding the cell at (x, y)
+    pub fn tile_of(&self, x: usize, y: usize) -> usize {
+        (y / TILE_SIZE) * self.width.div_ceil(TILE_SIZE) + x / TILE_SIZE
+    }
+    /// Marks the cell at the index as changed
+    pub fn push(&mut self, index: usize) {
+        let (x, y) = (index % self.width, index / self.width);
+        let tile = self.tile_of(x, y);
+        self.tiles[tile] = true;
+        self.near_edge |= self.is_near_edge(x, y);
+        self.cells.push(index);
+    }
+    fn is_near_edge(&self, x: usize, y: usize) -> bool {
+        let r = self.radius;
+        x < r || y < r || x + r >= self.width || y + r >= self.height
+    }
+    /// The indexes of every cell in the tile
+    pub fn tile_cells(&self, tile: usize) -> impl Iterator<Item = usize> {
+        let tiles_x = self.width.div_ceil(TILE_SIZE);
+        let (x0, y0) = ((tile % tiles_x) * TILE_SIZE, (tile / tiles_x) * TILE_SIZE);
+        let (x1, y1) = (
+            (x0 + TILE_SIZE).min(self.width),
+            (y0 + TILE_SIZE).min(self.height),
+        );
+        let width = self.width;
+        (y0..y1).flat_map(move |y| (x0..x1).map(move |x| y * width + x))
+    }
+    /// Which tiles might change in the next generation: every tile next to one that
+    /// changed. If the edges wrap around (see [crate::topology::Topology]), a change
+    /// near any edge could reach any other edge, so every tile near an edge is included
+    pub fn active_tiles(&self, wrapping: bool) -> Vec<bool> {
+        let tiles_x = self.width.div_ceil(TILE_SIZE);
+        let tiles_y = self.height.div_ceil(TILE_SIZE);
+        let mut active = vec![false; self.tiles.len()];
+        for ty in 0..tiles_y {
+            for tx in 0..tiles_x {
+                if !self.tiles[ty * tiles_x + tx] {
+                    continue;
+                }
+                for ny in ty.saturating_sub(1)..(ty + 2).min(tiles_y) {
+                    for nx in tx.saturating_sub(1)..(tx + 2).min(tiles_x) {
+                        active[ny * tiles_x + nx] = true;
+                    }
+                }
+            }
+        }
+        if wrapping && self.near_edge {
+            for ty in 0..tiles_y {
+                for tx in 0..tiles_x {
+                    let (x0, y0) = (tx * TILE_SIZE, ty * TILE_SIZE);
+                    let x1 = (x0 + TILE_SIZE).min(self.width) - 1;
+                    let y1 = (y0 + TILE_SIZE).min(self.height) - 1;
+                    if self.is_near_edge(x0, y0) || self.is_near_edge(x1, y1) {
+                        active[ty * tiles_x + tx] = true;
+                    }
+                }
+            }
+        }
+        active
+    }
+}
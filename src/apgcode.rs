@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+
+use crate::game::{CellState, Game};
+use crate::pattern::Rect;
+use crate::rule::Rule;
+use crate::sparse::SparseGame;
+
+/// How far an object is run looking for its period
+pub const MAX_PERIOD: u64 = 1000;
+/// The characters used for the columns of a strip & the lengths of runs of empty columns
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// Each strip of an apgcode is this many rows tall
+const STRIP_HEIGHT: i64 = 5;
+
+/// An (x, y) position, w/ y going down
+type Position = (i64, i64);
+
+/// What kind of object an apgcode is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectKind {
+    /// Never changes ("xs")
+    StillLife,
+    /// Comes back to where it started ("xp")
+    Oscillator,
+    /// Comes back somewhere else ("xq")
+    Spaceship,
+}
+impl ObjectKind {
+    fn prefix(&self) -> &'static str {
+        match self {
+            ObjectKind::StillLife => "xs",
+            ObjectKind::Oscillator => "xp",
+            ObjectKind::Spaceship => "xq",
+        }
+    }
+}
+
+/// The name apgsearch & Catagolue give an object, eg. "xs4_33" for a block or
+/// "xq4_153" for a glider. The prefix has the kind of object & its population (still
+/// lifes) or period (the rest), then after the '_' are its cells in extended Wechsler
+/// format:
+/// - The object is cut into strips 5 rows tall, separated by 'z'
+/// - Each column of a strip is one of 0 to 9 & a to v, the bits being its cells
+///   from the top down
+/// - Runs of empty columns are shortened to 'w' (2), 'x' (3) or 'y' & the length
+///   less 4 (4 to 39), & they're left off the ends of strips
+///
+/// The same object in any orientation (or phase) gets the same code, the shortest
+/// one (then the first alphabetically) is picked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Apgcode {
+    pub kind: ObjectKind,
+    /// The population of still lifes & the period of everything else
+    pub number: u64,
+    /// The cells, in extended Wechsler format
+    pub wechsler: String,
+}
+#[allow(unused)]
+impl Apgcode {
+    /// The code of the object in a rectangle of a board, run under the board's rule.
+    /// Fails if the object isn't a still life, oscillator or spaceship (within
+    /// [MAX_PERIOD] generations), or if the rule can't be run on an infinite board
+    pub fn from_region(game: &Game, area: Rect) -> Result<Self, &'static str> {
+        let pattern = game.copy(area)?;
+        let cells: Vec<Position> = (0..pattern.height())
+            .flat_map(|y| (0..pattern.width()).map(move |x| (x, y)))
+            .filter(|(x, y)| pattern.get(*x, *y) == CellState::Alive)
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect();
+        Apgcode::from_cells(&cells, game.rule())
+    }
+    /// The code of the object made of the given living cells
+    pub fn from_cells(cells: &[Position], rule: Rule) -> Result<Self, &'static str> {
+        if cells.is_empty() {
+            return Err("There's nothing alive to name");
+        }
+        let mut life = SparseGame::new();
+        life.set_rule(rule)?;
+        cells
+            .iter()
+            .for_each(|(x, y)| life.set(*x, *y, CellState::Alive));
+
+        let (start, start_corner) = normalised(life.live_cells());
+        let mut phases = vec![start.clone()];
+        for generation in 1..=MAX_PERIOD {
+            life.step(1);
+            if life.population() == 0 {
+                return Err("The object dies out");
+            }
+            let (cells, corner) = normalised(life.live_cells());
+            if cells != start {
+                phases.push(cells);
+                continue;
+            }
+            let kind = match (corner == start_corner, generation) {
+                (true, 1) => ObjectKind::StillLife,
+                (true, _) => ObjectKind::Oscillator,
+                (false, _) => ObjectKind::Spaceship,
+            };
+            let number = match kind {
+                ObjectKind::StillLife => start.len() as u64,
+                _ => generation,
+            };
+            let wechsler = phases
+                .iter()
+                .flat_map(|phase| orientations(phase))
+                .map(|cells| wechsler(&cells))
+                .min_by(|a, b| (a.len(), a).cmp(&(b.len(), b)))
+                .expect("there's always a phase");
+            return Ok(Apgcode {
+                kind,
+                number,
+                wechsler,
+            });
+        }
+        Err("The object isn't a still life, oscillator or spaceship (or its period is too long)")
+    }
+    /// The living cells of the code, w/ the top left of the first strip at (0, 0)
+    pub fn cells(&self) -> Vec<Position> {
+        unwechsler(&self.wechsler).expect("the code was checked when it was made")
+    }
+    /// The object on a board just big enough for it, run under the given rule
+    pub fn to_game(&self, rule: Rule) -> Game {
+        let cells = self.cells();
+        let width = cells.iter().map(|(x, _)| *x).max().unwrap_or(0) as usize + 1;
+        let height = cells.iter().map(|(_, y)| *y).max().unwrap_or(0) as usize + 1;
+        let mut board = vec![CellState::Dead; width * height];
+        for (x, y) in cells {
+            board[y as usize * width + x as usize] = CellState::Alive;
+        }
+        let mut game = Game::new(width, height);
+        game.set_rule(rule);
+        game.replace_buffer(board).unwrap();
+        game
+    }
+}
+impl std::fmt::Display for Apgcode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}_{}", self.kind.prefix(), self.number, self.wechsler)
+    }
+}
+impl std::str::FromStr for Apgcode {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, wechsler) = s
+            .trim()
+            .split_once('_')
+            .ok_or("Expected an apgcode like \"xs4_33\"")?;
+        let kind = match prefix.get(..2) {
+            Some("xs") => ObjectKind::StillLife,
+            Some("xp") => ObjectKind::Oscillator,
+            Some("xq") => ObjectKind::Spaceship,
+            _ => return Err("Expected an apgcode to start w/ xs, xp or xq"),
+        };
+        let number = prefix[2..]
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or("Couldn't parse the population or period of the apgcode")?;
+        if unwechsler(wechsler)?.is_empty() {
+            return Err("There's nothing alive in the apgcode");
+        }
+        Ok(Apgcode {
+            kind,
+            number,
+            wechsler: wechsler.to_string(),
+        })
+    }
+}
+
+/// The cells sorted & moved so their bounding box starts at (0, 0), & where its
+/// corner was
+fn normalised(cells: impl Iterator<Item = Position>) -> (Vec<Position>, Position) {
+    let mut cells: Vec<Position> = cells.collect();
+    let x0 = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let y0 = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    cells
+        .iter_mut()
+        .for_each(|(x, y)| (*x, *y) = (*x - x0, *y - y0));
+    cells.sort_unstable();
+    (cells, (x0, y0))
+}
+/// The cells turned & mirrored every way they can be (8 of them)
+fn orientations(cells: &[Position]) -> impl Iterator<Item = Vec<Position>> + '_ {
+    let turns: [fn(Position) -> Position; 8] = [
+        |(x, y)| (x, y),
+        |(x, y)| (-y, x),
+        |(x, y)| (-x, -y),
+        |(x, y)| (y, -x),
+        |(x, y)| (-x, y),
+        |(x, y)| (x, -y),
+        |(x, y)| (y, x),
+        |(x, y)| (-y, -x),
+    ];
+    turns
+        .into_iter()
+        .map(|turn| normalised(cells.iter().map(|c| turn(*c))).0)
+}
+/// Writes cells (w/ their bounding box at (0, 0)) in extended Wechsler format
+fn wechsler(cells: &[Position]) -> String {
+    let alive: HashSet<Position> = cells.iter().copied().collect();
+    let width = cells.iter().map(|(x, _)| *x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|(_, y)| *y + 1).max().unwrap_or(0);
+    let strips: Vec<String> = (0..(height + STRIP_HEIGHT - 1) / STRIP_HEIGHT)
+        .map(|strip| {
+            let mut code = String::new();
+            let mut empty = 0;
+            for x in 0..width {
+                let column = (0..STRIP_HEIGHT)
+                    .filter(|i| alive.contains(&(x, strip * STRIP_HEIGHT + i)))
+                    .fold(0, |column, i| column | 1 << i);
+                if column == 0 {
+                    empty += 1;
+                    continue;
+                }
+                push_empty_columns(&mut code, empty);
+                empty = 0;
+                code.push(DIGITS[column] as char);
+            }
+            code
+        })
+        .collect();
+    strips.join("z")
+}
+/// Writes a run of empty columns, as short as it can be
+fn push_empty_columns(code: &mut String, mut empty: usize) {
+    while empty >= 40 {
+        code.push_str("yz");
+        empty -= 39;
+    }
+    match empty {
+        0 => {}
+        1 => code.push('0'),
+        2 => code.push('w'),
+        3 => code.push('x'),
+        n => {
+            code.push('y');
+            code.push(DIGITS[n - 4] as char);
+        }
+    }
+}
+/// Reads cells written by [wechsler]
+fn unwechsler(code: &str) -> Result<Vec<Position>, &'static str> {
+    let digit = |c: char| DIGITS.iter().position(|d| *d as char == c);
+    let mut cells = Vec::new();
+    let (mut x, mut strip) = (0i64, 0i64);
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            'z' => (x, strip) = (0, strip + 1),
+            'w' => x += 2,
+            'x' => x += 3,
+            'y' => {
+                let run = chars
+                    .next()
+                    .and_then(digit)
+                    .ok_or("Expected a length after 'y' in the apgcode")?;
+                x += 4 + run as i64;
+            }
+            c => {
+                let column = digit(c)
+                    .filter(|d| *d < 32)
+                    .ok_or("The apgcode has a character that isn't a column")?;
+                for i in (0..STRIP_HEIGHT).filter(|i| column & 1 << i != 0) {
+                    cells.push((x, strip * STRIP_HEIGHT + i));
+                }
+                x += 1;
+            }
+        }
+    }
+    Ok(cells)
+}
@@ -0,0 +1,415 @@
+use crate::cycle::Cycle;
+use crate::game::CellState;
+use crate::hashlife::HashLife;
+use crate::pattern::{PasteMode, Pattern, Rect};
+use crate::resize::Resize;
+use crate::rule::Rule;
+use crate::soup::Soup;
+use crate::sparse::SparseGame;
+use crate::stats::Stats;
+use crate::topology::Topology;
+
+/// What every engine can do, so the front ends (text & GUI), renderers & save files
+/// can be written once and work w/ any of them.
+///
+/// A board is a width by height rectangle of cells, w/ (0, 0) in the top left corner.
+/// Engines w/o edges (like [SparseGame] & [HashLife]) are looked at through a [Viewport]
+pub trait Board {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    /// The cell at (x, y), None if it's off the board
+    fn get(&self, x: usize, y: usize) -> Option<CellState>;
+    /// Changes the cell at (x, y), panics if it's off the board
+    fn set(&mut self, x: usize, y: usize, cell: CellState);
+    /// Runs the board forward 'steps' generations
+    fn step(&mut self, steps: usize);
+    /// How many generations the board has been stepped
+    fn generation(&self) -> u64;
+    fn rule(&self) -> Rule;
+    /// Changes the rule, fails if the engine can't run it
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str>;
+    fn topology(&self) -> Topology;
+    /// Changes how the edges behave, fails if the engine doesn't support it
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str>;
+    /// Changes the size of the board, see [Resize]
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str>;
+
+    /// (width, height)
+    fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+    /// Every row of the board, from the top
+    fn rows(&self) -> impl Iterator<Item = Vec<CellState>> + '_
+    where
+        Self: Sized,
+    {
+        (0..self.height()).map(move |y| {
+            (0..self.width())
+                .map(|x| self.get(x, y).unwrap_or(CellState::Dead))
+                .collect()
+        })
+    }
+    /// Every cell, row by row (the way [crate::game::Game] stores them)
+    fn cells(&self) -> Vec<CellState> {
+        let width = self.width();
+        (0..width * self.height())
+            .map(|i| self.get(i % width, i / width).unwrap_or(CellState::Dead))
+            .collect()
+    }
+    /// Replaces every cell w/ ones from [Board::cells]
+    fn replace_cells(&mut self, cells: &[CellState]) -> Result<(), &'static str> {
+        let width = self.width();
+        if cells.len() != width * self.height() {
+            return Err("Can't replace the cells, there are too many or too few");
+        }
+        for (i, cell) in cells.iter().enumerate() {
+            self.set(i % width, i / width, *cell);
+        }
+        Ok(())
+    }
+    /// The position of every living cell, row by row
+    fn live_cells(&self) -> Vec<(usize, usize)> {
+        let width = self.width();
+        self.cells()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == CellState::Alive)
+            .map(|(i, _)| (i % width, i / width))
+            .collect()
+    }
+    /// How many cells are alive
+    fn population(&self) -> usize {
+        self.live_cells().len()
+    }
+    /// Changes the cells at 'pos', either all to 'cells[0]' or each to the one at the
+    /// same place in 'cells' (see [crate::game::Game::set_many])
+    fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
+        for (i, (x, y)) in pos.iter().enumerate() {
+            self.set(*x, *y, cells[if cells.len() == 1 { 0 } else { i }]);
+        }
+    }
+    /// Kills every cell
+    #[allow(unused)]
+    fn clear(&mut self) {
+        let dead = vec![CellState::Dead; self.width() * self.height()];
+        self.replace_cells(&dead).expect("the board is the right size");
+    }
+    /// Copies a rectangle of the board (clipped to the board) as a pattern
+    fn copy(&self, area: Rect) -> Result<Pattern, &'static str> {
+        let area = area
+            .clip(self.width(), self.height())
+            .ok_or("Can't copy, the region isn't on the board")?;
+        Ok(Pattern::copy_from(&self.cells(), self.width(), area))
+    }
+    /// Copies a rectangle of the board like [Board::copy], then kills everything in it
+    fn cut(&mut self, area: Rect) -> Result<Pattern, &'static str> {
+        let pattern = self.copy(area)?;
+        let dead = Pattern::dead(pattern.width(), pattern.height());
+        self.paste(&dead, (area.x, area.y), PasteMode::Overwrite);
+        Ok(pattern)
+    }
+    /// Pastes a pattern w/ its top left corner at 'at', anything that doesn't fit on
+    /// the board is left off
+    fn paste(&mut self, pattern: &Pattern, at: (usize, usize), mode: PasteMode) {
+        let mut cells = self.cells();
+        pattern.paste_onto(&mut cells, self.width(), at, mode);
+        self.replace_cells(&cells).expect("the cells came from the board");
+    }
+    /// Fills the board (or the soup's region of it) w/ a random soup
+    fn apply_soup(&mut self, soup: &Soup) -> Result<(), &'static str> {
+        let mut cells = self.cells();
+        soup.paste_onto(&mut cells, self.width())?;
+        self.replace_cells(&cells)
+    }
+    /// The generation, population & where the living cells are. Engines that keep
+    /// the last generation also count its births & deaths
+    fn stats(&self) -> Stats {
+        Stats::measure(&self.cells(), self.width(), self.generation())
+    }
+
+    // Engines that keep more about the board (like [crate::game::Game]) override
+    // these, the rest go w/o history, cycles & threads
+
+    /// How many times the board has been edited (see [crate::game::Game::edits])
+    fn edits(&self) -> u64 {
+        0
+    }
+    /// The cells that changed in the last generation, None if the board was edited
+    /// since or the engine doesn't keep track
+    fn changed_cells(&self) -> Option<Vec<(usize, usize)>> {
+        None
+    }
+    /// The cycle the board has settled into, if it's been noticed
+    fn cycle(&self) -> Option<Cycle> {
+        None
+    }
+    /// Undoes the last edit or generation, returns false if there was nothing to undo
+    fn undo(&mut self) -> bool {
+        false
+    }
+    /// Redoes the last undone edit or generation, returns false if there was nothing
+    fn redo(&mut self) -> bool {
+        false
+    }
+    /// How many threads are used to step the board
+    fn threads(&self) -> usize {
+        1
+    }
+    /// Changes how many threads are used to step the board, if the engine can use more
+    fn set_threads(&mut self, _threads: usize) {}
+}
+
+/// A boxed board is a board, so the front ends can run an engine picked when the
+/// program starts (see [crate::verify::ENGINES])
+impl<B: Board + ?Sized> Board for Box<B> {
+    fn width(&self) -> usize {
+        (**self).width()
+    }
+    fn height(&self) -> usize {
+        (**self).height()
+    }
+    fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        (**self).get(x, y)
+    }
+    fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        (**self).set(x, y, cell)
+    }
+    fn step(&mut self, steps: usize) {
+        (**self).step(steps)
+    }
+    fn rule(&self) -> Rule {
+        (**self).rule()
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        (**self).set_rule(rule)
+    }
+    fn topology(&self) -> Topology {
+        (**self).topology()
+    }
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        (**self).set_topology(topology)
+    }
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        (**self).resize(resize)
+    }
+    fn cells(&self) -> Vec<CellState> {
+        (**self).cells()
+    }
+    fn replace_cells(&mut self, cells: &[CellState]) -> Result<(), &'static str> {
+        (**self).replace_cells(cells)
+    }
+    fn live_cells(&self) -> Vec<(usize, usize)> {
+        (**self).live_cells()
+    }
+    fn population(&self) -> usize {
+        (**self).population()
+    }
+    fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
+        (**self).set_many(pos, cells)
+    }
+    fn clear(&mut self) {
+        (**self).clear()
+    }
+    fn copy(&self, area: Rect) -> Result<Pattern, &'static str> {
+        (**self).copy(area)
+    }
+    fn cut(&mut self, area: Rect) -> Result<Pattern, &'static str> {
+        (**self).cut(area)
+    }
+    fn paste(&mut self, pattern: &Pattern, at: (usize, usize), mode: PasteMode) {
+        (**self).paste(pattern, at, mode)
+    }
+    fn apply_soup(&mut self, soup: &Soup) -> Result<(), &'static str> {
+        (**self).apply_soup(soup)
+    }
+    fn stats(&self) -> Stats {
+        (**self).stats()
+    }
+    fn generation(&self) -> u64 {
+        (**self).generation()
+    }
+    fn edits(&self) -> u64 {
+        (**self).edits()
+    }
+    fn changed_cells(&self) -> Option<Vec<(usize, usize)>> {
+        (**self).changed_cells()
+    }
+    fn cycle(&self) -> Option<Cycle> {
+        (**self).cycle()
+    }
+    fn undo(&mut self) -> bool {
+        (**self).undo()
+    }
+    fn redo(&mut self) -> bool {
+        (**self).redo()
+    }
+    fn threads(&self) -> usize {
+        (**self).threads()
+    }
+    fn set_threads(&mut self, threads: usize) {
+        (**self).set_threads(threads)
+    }
+}
+
+/// Writes a board as text for the terminal, one row per line
+pub fn render(board: &impl Board) -> String {
+    board.rows().map(|row| format!("{row:?}\n")).collect()
+}
+
+/// An engine w/o edges (or a size), where cells can be anywhere. See [Viewport]
+pub trait Unbounded {
+    fn get(&self, x: i64, y: i64) -> CellState;
+    fn set(&mut self, x: i64, y: i64, cell: CellState);
+    fn step(&mut self, steps: usize);
+    /// How many generations the board has been stepped
+    fn generation(&self) -> u64;
+    fn rule(&self) -> Rule;
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str>;
+    /// The smallest rectangle holding every living cell, as ((min x, min y), (max x, max y))
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))>;
+}
+
+/// A rectangle of an [Unbounded] engine, so it can be used as a [Board]. Patterns can
+/// leave the viewport & come back, nothing is lost at the edges.
+///
+/// Resizing moves the viewport instead of the cells, eg. cropping to the living cells
+/// makes the viewport fit around them
+#[derive(Debug, Clone)]
+pub struct Viewport<E> {
+    pub engine: E,
+    /// Where the top left corner of the viewport is on the engine's board
+    pub origin: (i64, i64),
+    width: usize,
+    height: usize,
+}
+#[allow(unused)]
+impl<E: Unbounded> Viewport<E> {
+    pub fn new(engine: E, origin: (i64, i64), width: usize, height: usize) -> Self {
+        Viewport {
+            engine,
+            origin,
+            width,
+            height,
+        }
+    }
+    /// Where (x, y) in the viewport is on the engine's board
+    fn to_engine(&self, x: usize, y: usize) -> (i64, i64) {
+        (self.origin.0 + x as i64, self.origin.1 + y as i64)
+    }
+}
+impl<E: Unbounded> Board for Viewport<E> {
+    fn width(&self) -> usize {
+        self.width
+    }
+    fn height(&self) -> usize {
+        self.height
+    }
+    fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let (x, y) = self.to_engine(x, y);
+        Some(self.engine.get(x, y))
+    }
+    fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        assert!(x < self.width && y < self.height);
+        let (x, y) = self.to_engine(x, y);
+        self.engine.set(x, y, cell);
+    }
+    fn step(&mut self, steps: usize) {
+        self.engine.step(steps);
+    }
+    fn generation(&self) -> u64 {
+        self.engine.generation()
+    }
+    fn rule(&self) -> Rule {
+        self.engine.rule()
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        self.engine.set_rule(rule)
+    }
+    /// There are no edges, cells past the viewport aren't dead
+    fn topology(&self) -> Topology {
+        Topology::Unbounded
+    }
+    /// Bounded save files are accepted too, their cells are put on the infinite board
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        match topology {
+            Topology::Unbounded | Topology::Bounded => Ok(()),
+            _ => Err("infinite boards don't have edges"),
+        }
+    }
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        let (x, y) = self.origin;
+        let (origin, (width, height)) = match resize {
+            Resize::To(width, height, anchor) => {
+                let (dx, dy) = anchor.offset((self.width, self.height), (width, height));
+                ((x - dx as i64, y - dy as i64), (width, height))
+            }
+            Resize::Pad(n) => (
+                (x - n as i64, y - n as i64),
+                (self.width + 2 * n, self.height + 2 * n),
+            ),
+            Resize::CropToLive => {
+                let ((x0, y0), (x1, y1)) = self
+                    .engine
+                    .bounding_box()
+                    .ok_or("Can't crop, nothing is alive")?;
+                ((x0, y0), ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize))
+            }
+        };
+        if width == 0 || height == 0 {
+            return Err("The board can't be empty");
+        }
+        self.origin = origin;
+        (self.width, self.height) = (width, height);
+        Ok(())
+    }
+}
+
+impl Unbounded for SparseGame {
+    fn get(&self, x: i64, y: i64) -> CellState {
+        SparseGame::get(self, x, y)
+    }
+    fn set(&mut self, x: i64, y: i64, cell: CellState) {
+        SparseGame::set(self, x, y, cell)
+    }
+    fn step(&mut self, steps: usize) {
+        SparseGame::step(self, steps)
+    }
+    fn generation(&self) -> u64 {
+        SparseGame::generation(self)
+    }
+    fn rule(&self) -> Rule {
+        SparseGame::rule(self)
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        SparseGame::set_rule(self, rule)
+    }
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        SparseGame::bounding_box(self)
+    }
+}
+impl Unbounded for HashLife {
+    fn get(&self, x: i64, y: i64) -> CellState {
+        HashLife::get(self, x, y)
+    }
+    fn set(&mut self, x: i64, y: i64, cell: CellState) {
+        HashLife::set(self, x, y, cell)
+    }
+    fn step(&mut self, steps: usize) {
+        HashLife::step(self, steps as u64)
+    }
+    fn generation(&self) -> u64 {
+        HashLife::generation(self)
+    }
+    fn rule(&self) -> Rule {
+        HashLife::rule(self)
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        HashLife::set_rule(self, rule)
+    }
+    fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        HashLife::bounding_box(self)
+    }
+}
@@ -0,0 +1,252 @@
+use crate::game::CellState;
+use crate::pattern::{Pattern, Transform};
+
+/// The patterns every catalogue starts with, as (name, description, rows) where
+/// 'O' is a living cell & anything else is dead
+const BUILTIN: &[(&str, &str, &[&str])] = &[
+    ("block", "The most common still life", &["OO", "OO"]),
+    ("beehive", "A 6 cell still life", &[".OO.", "O..O", ".OO."]),
+    (
+        "loaf",
+        "A 7 cell still life",
+        &[".OO.", "O..O", ".O.O", "..O."],
+    ),
+    ("boat", "A 5 cell still life", &["OO.", "O.O", ".O."]),
+    ("blinker", "The smallest oscillator, period 2", &["OOO"]),
+    ("toad", "A period 2 oscillator", &[".OOO", "OOO."]),
+    (
+        "beacon",
+        "A period 2 oscillator made of 2 blocks",
+        &["OO..", "OO..", "..OO", "..OO"],
+    ),
+    (
+        "pulsar",
+        "The most common period 3 oscillator",
+        &[
+            "..OOO...OOO..",
+            ".............",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            "..OOO...OOO..",
+            ".............",
+            "..OOO...OOO..",
+            "O....O.O....O",
+            "O....O.O....O",
+            "O....O.O....O",
+            ".............",
+            "..OOO...OOO..",
+        ],
+    ),
+    (
+        "pentadecathlon",
+        "A period 15 oscillator",
+        &["..O....O..", "OO.OOOO.OO", "..O....O.."],
+    ),
+    (
+        "glider",
+        "The smallest spaceship, moves diagonally by 1 cell every 4 generations",
+        &[".O.", "..O", "OOO"],
+    ),
+    (
+        "lwss",
+        "Lightweight spaceship, moves left by 2 cells every 4 generations",
+        &[".O..O", "O....", "O...O", "OOOO."],
+    ),
+    (
+        "mwss",
+        "Middleweight spaceship, moves left by 2 cells every 4 generations",
+        &["...O..", ".O...O", "O.....", "O....O", "OOOOO."],
+    ),
+    (
+        "hwss",
+        "Heavyweight spaceship, moves left by 2 cells every 4 generations",
+        &["...OO..", ".O....O", "O......", "O.....O", "OOOOOO."],
+    ),
+    (
+        "gosper-gun",
+        "Gosper glider gun, the first known gun. Fires a glider every 30 generations",
+        &[
+            "........................O...........",
+            "......................O.O...........",
+            "............OO......OO............OO",
+            "...........O...O....OO............OO",
+            "OO........O.....O...OO..............",
+            "OO........O...O.OO....O.O...........",
+            "..........O.....O.......O...........",
+            "...........O...O....................",
+            "............OO......................",
+        ],
+    ),
+    (
+        "r-pentomino",
+        "A methuselah, takes 1103 generations to settle down",
+        &[".OO", "OO.", ".O."],
+    ),
+    (
+        "acorn",
+        "A methuselah, takes 5206 generations to settle down",
+        &[".O.....", "...O...", "OO..OOO"],
+    ),
+    (
+        "diehard",
+        "A methuselah that dies out completely after 130 generations",
+        &["......O.", "OO......", ".O...OOO"],
+    ),
+];
+
+/// A named pattern in a [Catalogue]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub description: String,
+    pub pattern: Pattern,
+}
+
+/// A library of named patterns that can be stamped onto a board. It starts w/ the
+/// well known ones (see [Catalogue::builtin]), & more can be added
+#[derive(Debug, Clone)]
+pub struct Catalogue {
+    entries: Vec<Entry>,
+}
+impl Catalogue {
+    /// A catalogue w/ the built in patterns, eg. gliders, spaceships & the Gosper gun
+    pub fn builtin() -> Self {
+        let entries = BUILTIN
+            .iter()
+            .map(|(name, description, rows)| Entry {
+                name: name.to_string(),
+                description: description.to_string(),
+                pattern: pattern_from_rows(rows),
+            })
+            .collect();
+        Catalogue { entries }
+    }
+    /// Finds a pattern by name, ignoring case
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(name.trim()))
+    }
+    /// Adds a pattern, replacing any w/ the same name
+    pub fn add(&mut self, name: &str, description: &str, pattern: Pattern) {
+        let entry = Entry {
+            name: name.trim().to_string(),
+            description: description.trim().to_string(),
+            pattern,
+        };
+        match self
+            .entries
+            .iter_mut()
+            .find(|e| e.name.eq_ignore_ascii_case(name.trim()))
+        {
+            Some(old) => *old = entry,
+            None => self.entries.push(entry),
+        }
+    }
+    /// Every pattern, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> + '_ {
+        self.entries.iter()
+    }
+    /// A pattern by name, turned by each of the transforms in order
+    pub fn oriented(&self, name: &str, orientation: &[Transform]) -> Result<Pattern, String> {
+        let entry = self
+            .get(name)
+            .ok_or_else(|| format!("There's no pattern called \"{}\"", name.trim()))?;
+        Ok(orientation
+            .iter()
+            .fold(entry.pattern.clone(), |p, t| p.transformed(*t)))
+    }
+}
+impl Default for Catalogue {
+    fn default() -> Self {
+        Catalogue::builtin()
+    }
+}
+impl std::fmt::Display for Catalogue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in self.iter() {
+            let (w, h) = (entry.pattern.width(), entry.pattern.height());
+            writeln!(f, "{} ({w} by {h}): {}", entry.name, entry.description)?;
+        }
+        Ok(())
+    }
+}
+
+/// Something to do w/ a [Catalogue], parsed from commands like "place glider 10,10 rot90"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogueCommand {
+    /// Stamps a pattern w/ its top left corner at the position, after turning it by
+    /// each transform. Without a position it goes wherever the user is pointing (in the GUI)
+    Place {
+        name: String,
+        at: Option<(usize, usize)>,
+        orientation: Vec<Transform>,
+    },
+    /// Shows every pattern
+    List,
+    /// Adds the living cells from a save file as a new pattern
+    Add {
+        name: String,
+        path: String,
+        description: String,
+    },
+}
+impl std::str::FromStr for CatalogueCommand {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        match words.next().map(|w| w.to_lowercase()).as_deref() {
+            Some("place") => {
+                let name = words.next().ok_or("Which pattern should be placed?")?;
+                let mut at = None;
+                let mut orientation = Vec::new();
+                for word in words {
+                    match word.split_once(',') {
+                        Some((x, y)) => {
+                            let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+                                return Err("Couldn't parse the position, expected x,y");
+                            };
+                            at = Some((x, y));
+                        }
+                        None => orientation.push(word.parse()?),
+                    }
+                }
+                Ok(CatalogueCommand::Place {
+                    name: name.to_string(),
+                    at,
+                    orientation,
+                })
+            }
+            Some("list") => Ok(CatalogueCommand::List),
+            Some("add") => {
+                let name = words.next().ok_or("What should the pattern be called?")?;
+                let path = words
+                    .next()
+                    .ok_or("Which file should the pattern come from?")?;
+                Ok(CatalogueCommand::Add {
+                    name: name.to_string(),
+                    path: path.to_string(),
+                    description: words.collect::<Vec<&str>>().join(" "),
+                })
+            }
+            _ => Err("Unknown command, expected place, list or add"),
+        }
+    }
+}
+
+/// Reads a pattern from rows of text, 'O' is alive & anything else is dead.
+/// Short rows are filled out w/ dead cells
+fn pattern_from_rows(rows: &[&str]) -> Pattern {
+    let width = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+    let mut cells = Vec::with_capacity(width * rows.len());
+    for row in rows {
+        let len = row.chars().count();
+        cells.extend(row.chars().map(|c| match c {
+            'O' => CellState::Alive,
+            _ => CellState::Dead,
+        }));
+        cells.extend(std::iter::repeat_n(CellState::Dead, width - len));
+    }
+    Pattern::new(width, rows.len(), cells).expect("every row is the same width")
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use crate::game::CellState;
+
+/// A board that keeps coming back to the same states. A period of 1 is a board
+/// that has stopped changing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first generation of the cycle
+    pub start: u64,
+    pub period: u64,
+}
+impl std::fmt::Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.period {
+            1 => write!(f, "the board stopped changing at generation {}", self.start),
+            p => write!(
+                f,
+                "the board repeats every {p} generations, starting at generation {}",
+                self.start
+            ),
+        }
+    }
+}
+
+/// The hash of one cell of a board, see [board_hash]. Dead cells are 0, so they
+/// don't change the hash of the board
+pub fn cell_hash(index: usize, cell: CellState) -> u64 {
+    let state = match cell {
+        CellState::Dead => return 0,
+        CellState::Alive => 1,
+        CellState::Dying(left) => 1 + left as u64,
+    };
+    // splitmix64, to spread the index & state over all the bits
+    let mut z = ((index as u64) << 9 | state).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+/// The Zobrist hash of a board: every cell's [cell_hash] xor'd together. When some
+/// cells change, the hash can be kept up to date by xor'ing out their old hashes &
+/// in their new ones, w/o looking at the rest of the board
+pub fn board_hash(cells: &[CellState]) -> u64 {
+    cells
+        .iter()
+        .enumerate()
+        .fold(0, |hash, (i, cell)| hash ^ cell_hash(i, *cell))
+}
+
+/// Notices when a board goes back to a state it was in before, by keeping a hash
+/// of every generation it has seen.
+#[derive(Debug, Clone, Default)]
+pub struct CycleDetector {
+    /// The hash of each generation, and the first generation it was seen at
+    seen: HashMap<u64, u64>,
+    cycle: Option<Cycle>,
+}
+impl CycleDetector {
+    pub fn new() -> Self {
+        CycleDetector::default()
+    }
+    /// Records a generation of the board by the [board_hash] of its cells (eg. one kept
+    /// up to date as the board steps), generations should be recorded in order.
+    /// Returns the cycle once the board has been in this state before
+    pub fn observe_hash(&mut self, generation: u64, hash: u64) -> Option<Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle;
+        }
+        if let Some(start) = self.seen.insert(hash, generation) {
+            self.cycle = Some(Cycle {
+                start,
+                period: generation - start,
+            });
+            // The hashes aren't needed anymore, the cycle is known
+            self.seen = HashMap::new();
+        }
+        self.cycle
+    }
+    /// The cycle the board is in, if it has been found
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+    /// Whether nothing has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty() && self.cycle.is_none()
+    }
+    /// Forgets every generation, eg. after the board is edited
+    pub fn reset(&mut self) {
+        *self = CycleDetector::default();
+    }
+}
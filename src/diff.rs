@@ -0,0 +1,233 @@
+use crate::game::CellState;
+
+/// The first line of a diff written as text, followed by how many cells the board has
+const TEXT_HEADER: &str = "diff";
+
+/// The cells that differ between 2 boards (or 2 generations of one board) stored
+/// row by row, as (index, before, after) sorted by index.
+///
+/// A diff can be applied to a board to patch it forwards, or reverted to go back.
+/// It can be written as text (see the Display impl) or as bytes (see [Diff::to_bytes])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// How many cells the boards have
+    len: usize,
+    cells: Vec<(usize, CellState, CellState)>,
+}
+impl Diff {
+    /// The diff between 2 boards of the same size
+    pub fn between(before: &[CellState], after: &[CellState]) -> Result<Self, &'static str> {
+        if before.len() != after.len() {
+            return Err("Can't diff boards that aren't the same size");
+        }
+        Ok(Diff::between_indexes(before, after, 0..before.len()))
+    }
+    /// The diff between 2 boards of the same size, only looking at the cells at
+    /// 'indexes' (eg. the ones that might have changed in a generation)
+    pub fn between_indexes(
+        before: &[CellState],
+        after: &[CellState],
+        indexes: impl Iterator<Item = usize>,
+    ) -> Self {
+        let cells = indexes
+            .filter(|i| before[*i] != after[*i])
+            .map(|i| (i, before[i], after[i]))
+            .collect();
+        Diff::from_cells(before.len(), cells)
+    }
+    /// A diff from (index, before, after) for each changed cell, on a board of 'len'
+    /// cells. Cells that didn't change are dropped
+    pub fn from_cells(len: usize, mut cells: Vec<(usize, CellState, CellState)>) -> Self {
+        cells.retain(|(_, before, after)| before != after);
+        cells.sort_by_key(|(i, _, _)| *i);
+        Diff { len, cells }
+    }
+    /// How many cells the boards have
+    pub fn board_len(&self) -> usize {
+        self.len
+    }
+    /// How many cells changed
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+    /// Every changed cell as (index, before, after)
+    pub fn iter(&self) -> impl Iterator<Item = &(usize, CellState, CellState)> + '_ {
+        self.cells.iter()
+    }
+    /// The (x, y) position of every changed cell, on a board 'width' cells wide
+    #[allow(unused)]
+    pub fn positions(&self, width: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .iter()
+            .map(move |(i, _, _)| (i % width, i / width))
+    }
+    /// The diff going the other way, from 'after' back to 'before'
+    #[allow(unused)]
+    pub fn inverse(&self) -> Diff {
+        Diff {
+            len: self.len,
+            cells: self.cells.iter().map(|(i, b, a)| (*i, *a, *b)).collect(),
+        }
+    }
+    /// Patches a board, setting every changed cell to its 'after' state.
+    /// Fails (w/o changing anything) if the board isn't the right size
+    pub fn apply(&self, cells: &mut [CellState]) -> Result<(), &'static str> {
+        if cells.len() != self.len {
+            return Err("Can't apply the diff, the board isn't the same size");
+        }
+        self.cells
+            .iter()
+            .for_each(|(i, _, after)| cells[*i] = *after);
+        Ok(())
+    }
+    /// Undoes [Diff::apply], setting every changed cell back to its 'before' state
+    pub fn revert(&self, cells: &mut [CellState]) -> Result<(), &'static str> {
+        if cells.len() != self.len {
+            return Err("Can't revert the diff, the board isn't the same size");
+        }
+        // backwards, in case a cell changed more than once
+        self.cells
+            .iter()
+            .rev()
+            .for_each(|(i, before, _)| cells[*i] = *before);
+        Ok(())
+    }
+    /// Writes the diff compactly: the board's cell count & the number of changes as
+    /// little endian u64s, then for each change the gap from the last changed index
+    /// (as a LEB128 varint) & the before & after states as 1 byte each
+    #[allow(unused)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + self.cells.len() * 3);
+        bytes.extend((self.len as u64).to_le_bytes());
+        bytes.extend((self.cells.len() as u64).to_le_bytes());
+        let mut last = 0;
+        for (i, before, after) in &self.cells {
+            let mut gap = i - last;
+            last = *i;
+            loop {
+                let byte = (gap & 0x7f) as u8;
+                gap >>= 7;
+                match gap {
+                    0 => {
+                        bytes.push(byte);
+                        break;
+                    }
+                    _ => bytes.push(byte | 0x80),
+                }
+            }
+            bytes.push(cell_to_byte(*before));
+            bytes.push(cell_to_byte(*after));
+        }
+        bytes
+    }
+    /// Reads a diff written by [Diff::to_bytes]
+    #[allow(unused)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        const TRUNCATED: &str = "The diff ends too early";
+        let read_u64 = |at: usize| -> Result<u64, &'static str> {
+            let chunk = bytes.get(at..at + 8).ok_or(TRUNCATED)?;
+            Ok(u64::from_le_bytes(chunk.try_into().unwrap()))
+        };
+        let len = read_u64(0)? as usize;
+        let count = read_u64(8)? as usize;
+
+        let mut rest = bytes[16..].iter().copied();
+        let mut cells = Vec::new();
+        let mut index: usize = 0;
+        for _ in 0..count {
+            let mut gap: usize = 0;
+            for shift in (0..).step_by(7) {
+                let byte = rest.next().ok_or(TRUNCATED)?;
+                if shift >= usize::BITS {
+                    return Err("A cell index in the diff is too big");
+                }
+                gap |= ((byte & 0x7f) as usize) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+            index = index
+                .checked_add(gap)
+                .ok_or("A cell index in the diff is too big")?;
+            let before = byte_to_cell(rest.next().ok_or(TRUNCATED)?);
+            let after = byte_to_cell(rest.next().ok_or(TRUNCATED)?);
+            cells.push((index, before, after));
+        }
+        if rest.next().is_some() {
+            return Err("There's extra data after the diff");
+        }
+        check_indexes(len, &cells)?;
+        Ok(Diff { len, cells })
+    }
+}
+/// Writes the diff as text, eg.
+/// ```text
+/// diff 100
+/// 12 ✗✓
+/// 13 ✓1
+/// ```
+/// The first line has the board's cell count, then each changed cell is on a line w/
+/// its index & its states before & after (written like in save files)
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{TEXT_HEADER} {}", self.len)?;
+        for (i, before, after) in &self.cells {
+            write!(f, "\n{i} {before}{after}")?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for Diff {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim).filter(|l| !l.is_empty());
+        let len = lines
+            .next()
+            .and_then(|l| l.strip_prefix(TEXT_HEADER))
+            .and_then(|l| l.trim().parse().ok())
+            .ok_or("Expected a diff to start w/ \"diff <number of cells>\"")?;
+
+        let mut cells = Vec::new();
+        for line in lines {
+            let (index, states) = line
+                .split_once(' ')
+                .ok_or("Expected each change to be \"<index> <before><after>\"")?;
+            let index = index.parse().map_err(|_| "Couldn't parse a cell index")?;
+            let mut states = states.trim().chars().map(CellState::from_char);
+            match (states.next(), states.next(), states.next()) {
+                (Some(Some(before)), Some(Some(after)), None) => cells.push((index, before, after)),
+                _ => return Err("Couldn't parse the states of a cell"),
+            }
+        }
+        check_indexes(len, &cells)?;
+        Ok(Diff::from_cells(len, cells))
+    }
+}
+
+/// Makes sure every changed cell is on the board
+fn check_indexes(len: usize, cells: &[(usize, CellState, CellState)]) -> Result<(), &'static str> {
+    match cells.iter().all(|(i, _, _)| *i < len) {
+        true => Ok(()),
+        false => Err("A changed cell in the diff is off the board"),
+    }
+}
+/// 0 is dead, 1 is alive, & 1 + n is dying w/ n generations left
+#[allow(unused)]
+fn cell_to_byte(cell: CellState) -> u8 {
+    match cell {
+        CellState::Dead => 0,
+        CellState::Alive => 1,
+        CellState::Dying(left) => left.saturating_add(1),
+    }
+}
+#[allow(unused)]
+fn byte_to_cell(byte: u8) -> CellState {
+    match byte {
+        0 => CellState::Dead,
+        1 => CellState::Alive,
+        n => CellState::Dying(n - 1),
+    }
+}
@@ -1,6 +1,17 @@
 use std::fmt::Formatter;
 
-use crate::{ALIVE_STATUS_CHARACTER, DEAD_STATUS_CHARACTER};
+use crate::active::{Changes, TILE_SIZE};
+use crate::board::Board;
+use crate::cycle::{board_hash, cell_hash, Cycle, CycleDetector};
+use crate::diff::Diff;
+use crate::history::History;
+use crate::pattern::{PasteMode, Pattern, Rect};
+use crate::resize::{Anchor, Resize};
+use crate::rule::Rule;
+use crate::soup::{Soup, Symmetry};
+use crate::stats::Stats;
+use crate::topology::Topology;
+use crate::{ALIVE_STATUS_CHARACTER, DEAD_STATUS_CHARACTER, DYING_STATUS_CHARACTERS};
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
 #[allow(unused)]
@@ -14,16 +25,59 @@ pub enum GameAction {
     Save,
     Failed,
     Paused,
+    ChangeRule,
+    ChangeTopology,
+    Undo,
+    Redo,
+    Resize,
+    Clipboard,
+    Catalogue,
+    Randomize,
 }
 impl std::fmt::Display for GameAction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CellState {
     Alive,
     Dead,
+    /// A cell that used to be alive, and will be dead after this many more generations.
+    /// Only rules with more than 2 states (see [Rule::states]) have these
+    Dying(u8),
+}
+impl CellState {
+    /// Reads a cell back from the character it is displayed as
+    pub fn from_char(c: char) -> Option<CellState> {
+        match c {
+            ALIVE_STATUS_CHARACTER => Some(CellState::Alive),
+            DEAD_STATUS_CHARACTER => Some(CellState::Dead),
+            _ => DYING_STATUS_CHARACTERS
+                .chars()
+                .position(|d| d == c)
+                .map(|n| CellState::Dying(n as u8 + 1)),
+        }
+    }
+    /// The cell as a state number, the way most programs number them:
+    /// 0 is dead, 1 is alive, and 2 up to (states - 1) are dying
+    #[allow(unused)]
+    pub fn state_index(&self, states: u8) -> u8 {
+        match self {
+            CellState::Dead => 0,
+            CellState::Alive => 1,
+            CellState::Dying(left) => states - left,
+        }
+    }
+    /// Turns a state number back into a cell, see [CellState::state_index]
+    #[allow(unused)]
+    pub fn from_state_index(index: u8, states: u8) -> CellState {
+        match index {
+            0 => CellState::Dead,
+            1 => CellState::Alive,
+            _ => CellState::Dying(states - index),
+        }
+    }
 }
 #[allow(clippy::from_over_into)] // dumb lint
 impl From<CellState> for char {
@@ -31,6 +85,10 @@ impl From<CellState> for char {
         match value {
             CellState::Alive => ALIVE_STATUS_CHARACTER,
             CellState::Dead => DEAD_STATUS_CHARACTER,
+            CellState::Dying(left) => DYING_STATUS_CHARACTERS
+                .chars()
+                .nth(left as usize - 1)
+                .unwrap_or('?'),
         }
     }
 }
@@ -43,14 +101,7 @@ impl std::fmt::Debug for CellState {
 }
 impl std::fmt::Display for CellState {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                CellState::Alive => ALIVE_STATUS_CHARACTER,
-                CellState::Dead => DEAD_STATUS_CHARACTER,
-            }
-        )
+        write!(f, "{}", <CellState as Into<char>>::into(*self))
     }
 }
 
@@ -68,27 +119,215 @@ pub struct Game {
     pub(crate) y_max: usize,
     current: Vec<CellState>,
     previous: Vec<CellState>,
+    rule: Rule,
+    topology: Topology,
+    threads: usize,
+    history: History,
+    cycles: CycleDetector,
+    /// The [board_hash] of the cells, kept up to date as the board steps. It's only
+    /// worked out from scratch on the first step after an edit
+    hash: u64,
+    generation: u64,
+    /// How many times the board has been edited, see [Game::edits]
+    edits: u64,
+    /// What changed in the last generation, None if the board has been edited since
+    changes: Option<Changes>,
 }
 impl Game {
+    /// A dead board, which can't be empty (it panics if 'x' or 'y' is 0)
     pub fn new(x: usize, y: usize) -> Self {
+        Game::with_topology(x, y, Topology::default())
+    }
+    pub fn with_topology(x: usize, y: usize, topology: Topology) -> Self {
+        assert!(x > 0 && y > 0, "A board can't be empty");
         Game {
             x_max: x,
             y_max: y,
             current: vec![CellState::Dead; x * y],
             previous: vec![CellState::Dead; x * y],
+            rule: Rule::default(),
+            topology,
+            threads: 1,
+            history: History::default(),
+            cycles: CycleDetector::new(),
+            hash: 0,
+            generation: 0,
+            edits: 0,
+            changes: None,
+        }
+    }
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+        self.edited();
+    }
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+    /// Sets how many threads are used to step the board. The board is split into
+    /// that many bands of rows, which are worked out at the same time. The results
+    /// are the same no matter how many threads are used.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// Changes the rule used for every following step, the board itself is left alone
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.edited();
+    }
+    /// The cycle the board has settled into, if it has been stepped far enough (since
+    /// the last edit) to come back to an earlier state
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycles.cycle()
+    }
+    /// How many generations the board has been stepped
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// How many times the board has been changed by anything other than a step (this
+    /// includes undoing & redoing). If it's the same as it was, along w/ the generation,
+    /// the board hasn't changed
+    pub fn edits(&self) -> u64 {
+        self.edits
+    }
+    /// How many cells are alive
+    #[allow(unused)]
+    pub fn population(&self) -> usize {
+        self.current
+            .iter()
+            .filter(|c| **c == CellState::Alive)
+            .count()
+    }
+    /// The generation, population, births & deaths of the last generation, and where
+    /// the living cells are. Births & deaths are 0 once the board is edited
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::measure(&self.current, self.x_max, self.generation);
+        for index in self.changes.iter().flat_map(|c| &c.cells) {
+            match self.current[*index] {
+                CellState::Alive => stats.births += 1,
+                _ if self.previous[*index] == CellState::Alive => stats.deaths += 1,
+                _ => {}
+            }
         }
+        stats
+    }
+    /// The cells that changed in the last generation. None if the board was edited
+    /// since, then anything might have changed
+    pub fn changed_cells(&self) -> Option<impl Iterator<Item = (usize, usize)> + '_> {
+        let x_max = self.x_max;
+        let changes = self.changes.as_ref()?;
+        Some(changes.cells.iter().map(move |i| (i % x_max, i / x_max)))
+    }
+    /// The cells that differ between this board & another one of the same size, going
+    /// from this one to the other
+    #[allow(unused)]
+    pub fn diff(&self, other: &Game) -> Result<Diff, &'static str> {
+        if (self.x_max, self.y_max) != (other.x_max, other.y_max) {
+            return Err("Can't diff boards that aren't the same size");
+        }
+        Diff::between(&self.current, &other.current)
+    }
+    /// The cells that changed in the last generation as a diff, see [Game::changed_cells]
+    #[allow(unused)]
+    pub fn last_diff(&self) -> Option<Diff> {
+        let changes = self.changes.as_ref()?;
+        let indexes = changes.cells.iter().copied();
+        Some(Diff::between_indexes(
+            &self.previous,
+            &self.current,
+            indexes,
+        ))
+    }
+    /// Patches the board w/ a diff (eg. from another board), as an edit that can be undone
+    #[allow(unused)]
+    pub fn apply_diff(&mut self, diff: &Diff) -> Result<(), &'static str> {
+        if diff.board_len() != self.current.len() {
+            return Err("Can't apply the diff, the board isn't the same size");
+        }
+        self.history.record_diff(Diff::from_cells(
+            self.current.len(),
+            diff.iter()
+                .map(|(i, _, after)| (*i, self.current[*i], *after))
+                .collect(),
+        ));
+        diff.apply(&mut self.current)?;
+        self.edited();
+        Ok(())
+    }
+    /// Every cell, row by row
+    #[allow(unused)]
+    pub fn cells(&self) -> &[CellState] {
+        &self.current
+    }
+    /// Forgets what is known about the board's past, after it is changed by anything
+    /// other than a step
+    fn edited(&mut self) {
+        self.cycles.reset();
+        self.edits += 1;
+        self.changes = None;
+    }
+    /// How many edits & generations can be undone
+    #[allow(unused)]
+    pub fn history_limit(&self) -> usize {
+        self.history.limit()
+    }
+    /// Changes how many edits & generations are kept to be undone, 0 turns it off
+    #[allow(unused)]
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history.set_limit(limit);
+    }
+    /// Undoes the last edit or generation, returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo(&mut self.current) {
+            Some(steps) => {
+                self.edited();
+                self.generation -= steps;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Redoes the last undone edit or generation, returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo(&mut self.current) {
+            Some(steps) => {
+                self.edited();
+                self.generation += steps;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Makes a change to the whole board that can be undone
+    fn edit(&mut self, change: impl FnOnce(&mut Vec<CellState>)) {
+        let before = match self.history.limit() {
+            0 => Vec::new(),
+            _ => self.current.clone(),
+        };
+        change(&mut self.current);
+        self.history.record(&before, &self.current);
+        self.edited();
     }
     #[allow(unused)]
     pub fn get(&self, x: usize, y: usize) -> Option<CellState> {
         if !(0..self.x_max).contains(&x) || !(0..self.y_max).contains(&y) {
             return None;
         }
-        Some(self.current[y * self.y_max + x])
+        Some(self.current[y * self.x_max + x])
     }
     #[allow(unused)]
     pub fn set(&mut self, x: usize, y: usize, cell: CellState) {
         assert!((0..self.x_max).contains(&x) && (0..self.y_max).contains(&y));
-        self.current[y * self.y_max + x] = cell;
+        let index = y * self.x_max + x;
+        let diff = Diff::from_cells(self.current.len(), vec![(index, self.current[index], cell)]);
+        self.history.record_diff(diff);
+        self.current[index] = cell;
+        self.edited();
     }
     /// The 'cells' slice maybe either be len 1 (every position will be set to the same),
     /// or the same length as the coordinates (each position is set to the corresponding
@@ -98,59 +337,85 @@ impl Game {
     pub fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
         if cells.len() != 1 {
             assert_eq!(pos.len(), cells.len());
-            for (p, c) in pos.iter().zip(cells) {
-                let (x, y) = p;
-                self.current[y * self.y_max + x] = *c;
-            }
-            return;
         }
-        for p in pos.iter() {
-            let (x, y) = p;
-            self.current[y * self.y_max + x] = cells[0];
+        let mut changed = Vec::with_capacity(pos.len());
+        for (i, (x, y)) in pos.iter().enumerate() {
+            let index = y * self.x_max + x;
+            let cell = cells[if cells.len() == 1 { 0 } else { i }];
+            changed.push((index, self.current[index], cell));
+            self.current[index] = cell;
         }
+        self.history
+            .record_diff(Diff::from_cells(self.current.len(), changed));
+        self.edited();
     }
     #[allow(unused)]
     pub fn clear(&mut self) {
-        self.current.iter_mut().for_each(|c| *c = CellState::Dead);
+        self.edit(|cells| cells.iter_mut().for_each(|c| *c = CellState::Dead));
         self.previous.iter_mut().for_each(|c| *c = CellState::Dead);
     }
     #[allow(unused)]
     pub fn fill(&mut self) {
-        self.current.iter_mut().for_each(|c| *c = CellState::Alive);
+        self.edit(|cells| cells.iter_mut().for_each(|c| *c = CellState::Alive));
         self.previous.iter_mut().for_each(|c| *c = CellState::Alive);
     }
     fn iterate(&mut self) {
-        let (x_max, y_max) = (self.x_max as i32, self.y_max as i32);
-        for (cell_index, cell) in self.current.iter().enumerate() {
-            let (x, y) = (
-                (cell_index % self.x_max) as i32,
-                (cell_index / self.x_max) as i32,
-            );
-            let mut neighbors = vec![
-                (x - 1, y - 1),
-                (x, y - 1),
-                (x + 1, y - 1),
-                (x - 1, y),
-                (x + 1, y),
-                (x - 1, y + 1),
-                (x, y + 1),
-                (x + 1, y + 1),
-            ];
-            neighbors.retain(|(x, y)| (0..x_max).contains(x) && (0..y_max).contains(y));
-            let alive_neighbors: usize = neighbors
-                .into_iter()
-                .map(|(x, y)| self[(x as usize, y as usize)])
-                .map(|cell| if cell == CellState::Alive { 1 } else { 0 })
-                .sum();
+        if self.cycles.is_empty() {
+            self.hash = board_hash(&self.current);
+            self.cycles.observe_hash(self.generation, self.hash);
+        }
+        let rows_per_band = self.y_max.div_ceil(self.threads).max(1);
+        let band_len = (rows_per_band * self.x_max).max(1);
+        let (current, next) = (&self.current, &mut self.previous);
+        // Only the tiles near last generation's changes can change, if those are known
+        let active = self
+            .changes
+            .as_ref()
+            .map(|c| c.active_tiles(self.topology.wraps()));
+        let offsets = &self.rule.offsets();
+        let info = StepInfo {
+            size: (self.x_max, self.y_max),
+            rule: self.rule,
+            topology: self.topology,
+            offsets,
+            active: active.as_deref(),
+        };
 
-            self.previous[cell_index] = match cell {
-                CellState::Dead if alive_neighbors == 3 => CellState::Alive,
-                CellState::Alive if alive_neighbors == 2 => CellState::Alive,
-                CellState::Alive if alive_neighbors == 3 => CellState::Alive,
-                _ => CellState::Dead,
-            };
+        if self.threads == 1 {
+            step_band(current, next, 0, info);
+        } else {
+            // Every band reads from the whole (shared) current board, so the rows just
+            // past the edges of a band are there for all of them
+            std::thread::scope(|scope| {
+                for (band, next) in next.chunks_mut(band_len).enumerate() {
+                    let start = band * band_len;
+                    scope.spawn(move || step_band(current, next, start, info));
+                }
+            });
         }
         std::mem::swap(&mut self.current, &mut self.previous);
+        self.generation += 1;
+
+        let radius = self.rule.neighborhood().radius() as usize;
+        let mut changes = Changes::new(self.x_max, self.y_max, radius);
+        let (before, after) = (&self.previous, &self.current);
+        let candidates: Vec<usize> = match &active {
+            Some(active) => (0..active.len())
+                .filter(|t| active[*t])
+                .flat_map(|t| changes.tile_cells(t))
+                .collect(),
+            None => (0..after.len()).collect(),
+        };
+        for index in candidates {
+            if before[index] != after[index] {
+                changes.push(index);
+                self.hash ^= cell_hash(index, before[index]) ^ cell_hash(index, after[index]);
+            }
+        }
+        self.history
+            .record_step_changes(before, after, changes.cells.iter().copied());
+        self.cycles.observe_hash(self.generation, self.hash);
+        self.changes = Some(changes);
     }
     pub fn step(&mut self, steps: usize) {
         for _ in 0..steps {
@@ -166,19 +431,154 @@ impl Game {
             let (x, y) = (index % self.x_max, index / self.x_max);
             *cell = old.get(x, y);
         }
+        self.edited();
         Ok(())
     }
     pub fn rows(&self) -> impl Iterator<Item = &[CellState]> + '_ {
         self.current.chunks_exact(self.x_max)
     }
+    /// Changes the size of the board, the anchored part of it stays in place.
+    /// Cells past the new edges are lost & new cells are dead
+    #[allow(unused)]
+    pub fn resize(
+        &mut self,
+        width: usize,
+        height: usize,
+        anchor: Anchor,
+    ) -> Result<(), &'static str> {
+        self.apply_resize(Resize::To(width, height, anchor))
+    }
+    /// Adds 'n' dead cells to each side of the board
+    #[allow(unused)]
+    pub fn pad(&mut self, n: usize) -> Result<(), &'static str> {
+        self.apply_resize(Resize::Pad(n))
+    }
+    /// Shrinks the board to the smallest rectangle holding every living cell.
+    /// Fails if nothing is alive
+    #[allow(unused)]
+    pub fn crop_to_live(&mut self) -> Result<(), &'static str> {
+        self.apply_resize(Resize::CropToLive)
+    }
+    /// Copies a rectangle of the board, anything past the edges is left off.
+    /// Fails if none of the rectangle is on the board
+    pub fn copy(&self, area: Rect) -> Result<Pattern, &'static str> {
+        let area = area
+            .clip(self.x_max, self.y_max)
+            .ok_or("Can't copy, the region isn't on the board")?;
+        Ok(Pattern::copy_from(&self.current, self.x_max, area))
+    }
+    /// Copies a rectangle of the board like [Game::copy], then kills everything in it
+    pub fn cut(&mut self, area: Rect) -> Result<Pattern, &'static str> {
+        let pattern = self.copy(area)?;
+        let dead = Pattern::dead(pattern.width(), pattern.height());
+        self.paste(&dead, (area.x, area.y), PasteMode::Overwrite);
+        Ok(pattern)
+    }
+    /// Pastes a pattern w/ its top left corner at 'at', anything that doesn't fit on
+    /// the board is left off
+    pub fn paste(&mut self, pattern: &Pattern, at: (usize, usize), mode: PasteMode) {
+        let x_max = self.x_max;
+        self.edit(|cells| pattern.paste_onto(cells, x_max, at, mode));
+    }
+    /// Fills a rectangle of the board (anything past the edges is left off) w/ random
+    /// cells, each alive w/ a chance of 'density'. The same seed always gives the same
+    /// cells, & cells the symmetry maps onto each other are the same
+    #[allow(unused)]
+    pub fn randomize(
+        &mut self,
+        region: Rect,
+        density: f64,
+        seed: u64,
+        symmetry: Symmetry,
+    ) -> Result<(), &'static str> {
+        let soup = Soup {
+            region: Some(region),
+            density,
+            seed,
+            symmetry,
+        };
+        self.apply_soup(&soup)
+    }
+    /// Fills the board w/ a soup, see [Game::randomize]
+    pub fn apply_soup(&mut self, soup: &Soup) -> Result<(), &'static str> {
+        let x_max = self.x_max;
+        let mut result = Ok(());
+        self.edit(|cells| result = soup.paste_onto(cells, x_max));
+        result
+    }
+    /// Changes the size of the board. Resizing can't be undone, and the history of
+    /// the old size is dropped
+    pub fn apply_resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        let (cells, (width, height)) = resize.apply(&self.current, (self.x_max, self.y_max))?;
+        self.previous = cells.clone();
+        self.current = cells;
+        (self.x_max, self.y_max) = (width, height);
+        self.history.clear();
+        self.edited();
+        Ok(())
+    }
     pub fn replace_buffer(&mut self, new: Vec<CellState>) -> Result<(), &'static str> {
         if new.len() != self.current.len() {
             return Err("Can't replace Game buffer: new and old buffers are not the same length");
         }
-        self.current = new;
+        self.edit(|cells| *cells = new);
         Ok(())
     }
 }
+/// Everything needed to work out the next generation of a board
+#[derive(Clone, Copy)]
+struct StepInfo<'a> {
+    size: (usize, usize),
+    rule: Rule,
+    topology: Topology,
+    offsets: &'a [(isize, isize)],
+    /// Which tiles (see [Changes]) can change, None if any of them can
+    active: Option<&'a [bool]>,
+}
+/// Works out the next generation for part of a board. 'next' holds the cells from
+/// index 'start' onwards, and 'current' is the whole board.
+///
+/// Cells in tiles that can't change are skipped, 'next' has to hold the generation
+/// before 'current' for them to be right (which it does, as they didn't change)
+fn step_band(current: &[CellState], next: &mut [CellState], start: usize, info: StepInfo) {
+    let x_max = info.size.0;
+    let tiles_x = x_max.div_ceil(TILE_SIZE);
+    for (offset, new_cell) in next.iter_mut().enumerate() {
+        let cell_index = start + offset;
+        let (x, y) = (cell_index % x_max, cell_index / x_max);
+        if let Some(active) = info.active {
+            if !active[(y / TILE_SIZE) * tiles_x + x / TILE_SIZE] {
+                continue;
+            }
+        }
+        *new_cell = next_cell_state(
+            info.rule,
+            info.topology,
+            info.offsets,
+            (x, y),
+            info.size,
+            |nx, ny| current[ny * x_max + nx],
+        );
+    }
+}
+/// Works out what the cell at (x, y) becomes, counting its neighbors or (for isotropic
+/// rules) looking at how they are arranged. 'cell' is used to look up cells on the board
+fn next_cell_state(
+    rule: Rule,
+    topology: Topology,
+    offsets: &[(isize, isize)],
+    (x, y): (usize, usize),
+    (width, height): (usize, usize),
+    cell: impl Fn(usize, usize) -> CellState,
+) -> CellState {
+    if rule.is_isotropic() {
+        let mask = topology.neighbor_mask(x, y, width, height, offsets, &cell);
+        rule.next_state_isotropic(cell(x, y), mask)
+    } else {
+        let alive_neighbors = topology.alive_neighbors(x, y, width, height, offsets, &cell);
+        rule.next_state(cell(x, y), alive_neighbors)
+    }
+}
 impl std::ops::Index<(usize, usize)> for Game {
     type Output = CellState;
     fn index(&self, index: (usize, usize)) -> &Self::Output {
@@ -191,7 +591,7 @@ impl std::ops::Index<(usize, usize)> for Game {
             self.x_max,
             self.y_max
         );
-        &self.current[self.y_max * y + x]
+        &self.current[self.x_max * y + x]
     }
 }
 impl std::ops::IndexMut<(usize, usize)> for Game {
@@ -205,7 +605,9 @@ impl std::ops::IndexMut<(usize, usize)> for Game {
             self.x_max,
             self.y_max
         );
-        &mut self.current[self.y_max * y + x]
+        // Whatever gets written isn't tracked, so forget what changed last
+        self.edited();
+        &mut self.current[self.x_max * y + x]
     }
 }
 impl PartialEq for Game {
@@ -228,12 +630,107 @@ impl std::fmt::Display for Game {
         Ok(())
     }
 }
+impl Board for Game {
+    fn width(&self) -> usize {
+        self.x_max
+    }
+    fn height(&self) -> usize {
+        self.y_max
+    }
+    fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        Game::get(self, x, y)
+    }
+    fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        Game::set(self, x, y, cell)
+    }
+    fn step(&mut self, steps: usize) {
+        Game::step(self, steps)
+    }
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        Game::set_rule(self, rule);
+        Ok(())
+    }
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        if topology == Topology::Unbounded {
+            return Err("Only infinite boards have no edges");
+        }
+        Game::set_topology(self, topology);
+        Ok(())
+    }
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        self.apply_resize(resize)
+    }
+    fn cells(&self) -> Vec<CellState> {
+        self.current.clone()
+    }
+    fn replace_cells(&mut self, cells: &[CellState]) -> Result<(), &'static str> {
+        self.replace_buffer(cells.to_vec())
+    }
+    fn population(&self) -> usize {
+        Game::population(self)
+    }
+    fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
+        Game::set_many(self, pos, cells)
+    }
+    fn clear(&mut self) {
+        Game::clear(self)
+    }
+    fn copy(&self, area: Rect) -> Result<Pattern, &'static str> {
+        Game::copy(self, area)
+    }
+    fn cut(&mut self, area: Rect) -> Result<Pattern, &'static str> {
+        Game::cut(self, area)
+    }
+    fn paste(&mut self, pattern: &Pattern, at: (usize, usize), mode: PasteMode) {
+        Game::paste(self, pattern, at, mode)
+    }
+    fn apply_soup(&mut self, soup: &Soup) -> Result<(), &'static str> {
+        Game::apply_soup(self, soup)
+    }
+    fn stats(&self) -> Stats {
+        Game::stats(self)
+    }
+    fn generation(&self) -> u64 {
+        Game::generation(self)
+    }
+    fn edits(&self) -> u64 {
+        Game::edits(self)
+    }
+    fn changed_cells(&self) -> Option<Vec<(usize, usize)>> {
+        Game::changed_cells(self).map(Iterator::collect)
+    }
+    fn cycle(&self) -> Option<Cycle> {
+        Game::cycle(self)
+    }
+    fn undo(&mut self) -> bool {
+        Game::undo(self)
+    }
+    fn redo(&mut self) -> bool {
+        Game::redo(self)
+    }
+    fn threads(&self) -> usize {
+        Game::threads(self)
+    }
+    fn set_threads(&mut self, threads: usize) {
+        Game::set_threads(self, threads)
+    }
+}
 
 #[derive(Clone)]
 pub struct GameBoardOld {
     pub space: Vec<Vec<CellState>>,
     pub x_max: usize,
     pub y_max: usize,
+    pub rule: Rule,
+    pub topology: Topology,
+    /// How many generations the board has been stepped
+    pub generation: u64,
 }
 #[allow(unused)]
 impl GameBoardOld {
@@ -258,6 +755,9 @@ impl GameBoardOld {
             space: vec![vec![CellState::Dead; x]; y],
             x_max: x,
             y_max: y,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            generation: 0,
         }
     }
     ///Returns whether the board has any Alive cells in it
@@ -278,28 +778,10 @@ impl GameBoardOld {
     }
     pub(crate) fn update_to(&self, other: &mut GameBoardOld) {
         other.clear();
-        for (y, row) in self.space.iter().enumerate() {
-            for (x, cell) in row.iter().enumerate() {
-                let neighbors = num_alive_neighbors(self, x, y);
-                use CellState as S;
-                let new_cell = match cell {
-                    S::Alive => {
-                        if neighbors == 2 || neighbors == 3 {
-                            S::Alive
-                        } else {
-                            S::Dead
-                        }
-                    }
-
-                    S::Dead => {
-                        if neighbors == 3 {
-                            S::Alive
-                        } else {
-                            S::Dead
-                        }
-                    }
-                };
-                other.set(x, y, new_cell);
+        let offsets = self.rule.offsets();
+        for y in 0..self.y_max {
+            for x in 0..self.x_max {
+                other.set(x, y, next_old_cell_state(self, &offsets, x, y));
             }
         }
     }
@@ -310,10 +792,7 @@ impl GameBoardOld {
 }
 impl std::fmt::Display for GameBoardOld {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in self.space.iter() {
-            writeln!(f, "{:?}", row)?
-        }
-        Ok(())
+        write!(f, "{}", crate::board::render(self))
     }
 }
 impl PartialEq for GameBoardOld {
@@ -329,8 +808,69 @@ impl PartialEq for GameBoardOld {
         true
     }
 }
+impl Board for GameBoardOld {
+    fn width(&self) -> usize {
+        self.x_max
+    }
+    fn height(&self) -> usize {
+        self.y_max
+    }
+    fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        self.space.get(y)?.get(x).copied()
+    }
+    fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        self.space[y][x] = cell;
+    }
+    fn step(&mut self, steps: usize) {
+        *self = run_iterations(self, steps);
+    }
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        self.rule = rule;
+        Ok(())
+    }
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        if topology == Topology::Unbounded {
+            return Err("Only infinite boards have no edges");
+        }
+        self.topology = topology;
+        Ok(())
+    }
+    /// See [Game::apply_resize]
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        let (cells, (width, height)) = resize.apply(&self.cells(), (self.x_max, self.y_max))?;
+        self.space = cells.chunks_exact(width).map(|row| row.to_vec()).collect();
+        (self.x_max, self.y_max) = (width, height);
+        Ok(())
+    }
+    fn replace_cells(&mut self, cells: &[CellState]) -> Result<(), &'static str> {
+        if cells.len() != self.x_max * self.y_max {
+            return Err("Can't replace the cells, there are too many or too few");
+        }
+        for (row, new) in self.space.iter_mut().zip(cells.chunks_exact(self.x_max)) {
+            row.copy_from_slice(new);
+        }
+        Ok(())
+    }
+    fn population(&self) -> usize {
+        self.space
+            .iter()
+            .flatten()
+            .filter(|c| **c == CellState::Alive)
+            .count()
+    }
+}
 /// Returns a vector with the coordinates of all the given cell's neighbors
 /// If the given coordinates are outside the board, it will return an empty vec
+#[allow(unused)]
 pub fn get_neighbors(board: &GameBoardOld, x: usize, y: usize) -> Vec<(usize, usize)> {
     /* Number of refactors this function has had: ||||||||
      * swear to god this has made me lose interest in this project at least 3 times, I hate it so much
@@ -356,32 +896,39 @@ pub fn get_neighbors(board: &GameBoardOld, x: usize, y: usize) -> Vec<(usize, us
 /// Returns the next iteration of the given board, w/ the same dimensions
 fn update_board(old_board: &GameBoardOld) -> GameBoardOld {
     let mut new_board = GameBoardOld::new(old_board.x_max, old_board.y_max);
-    for (y, row) in old_board.space.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            let neighbors = num_alive_neighbors(old_board, x, y);
-            match cell {
-                CellState::Alive if neighbors == 2 || neighbors == 3 => {
-                    new_board.set(x, y, CellState::Alive)
-                }
-                CellState::Dead if neighbors == 3 => new_board.set(x, y, CellState::Alive),
-                _ => {} // Cells are dead by default
-            }
+    new_board.rule = old_board.rule;
+    new_board.topology = old_board.topology;
+    new_board.generation = old_board.generation + 1;
+    let offsets = old_board.rule.offsets();
+    for y in 0..old_board.y_max {
+        for x in 0..old_board.x_max {
+            new_board.set(x, y, next_old_cell_state(old_board, &offsets, x, y));
         }
     }
     new_board
 }
+fn next_old_cell_state(
+    board: &GameBoardOld,
+    offsets: &[(isize, isize)],
+    x: usize,
+    y: usize,
+) -> CellState {
+    let size = (board.x_max, board.y_max);
+    next_cell_state(board.rule, board.topology, offsets, (x, y), size, |x, y| {
+        board.space[y][x]
+    })
+}
 
 /// Counts the number of living neighbors a given cell has; as usize
+/// Cells past the edge of the board are handled by the board's topology
+#[allow(unused)]
 pub fn num_alive_neighbors(board: &GameBoardOld, x: usize, y: usize) -> usize {
-    let mut count: usize = 0;
-    for cell in get_neighbors(board, x, y) {
-        // Loop through neighbor cells
-        match board.get(cell.0, cell.1) {
-            CellState::Alive => count += 1,
-            CellState::Dead => {}
-        }
-    }
-    count
+    let offsets = board.rule.offsets();
+    board
+        .topology
+        .alive_neighbors(x, y, board.x_max, board.y_max, &offsets, |x, y| {
+            board.space[y][x]
+        })
 }
 /// Returns the board after n iterations
 pub fn run_iterations(board: &GameBoardOld, n: usize) -> GameBoardOld {
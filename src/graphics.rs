@@ -1,9 +1,15 @@
+use crate::board::Board;
+use crate::catalogue::{Catalogue, CatalogueCommand};
+use crate::cycle::Cycle;
 use crate::game::CellState;
+use crate::pattern::{PasteMode, Pattern, Rect, Transform};
 use crate::{game, save_load, text, GAME_X, GAME_Y};
 use pixels::{Pixels, PixelsBuilder, SurfaceTexture};
 use std::collections::VecDeque;
 use winit::dpi::PhysicalSize;
-use winit::event::{ElementState, Event, VirtualKeyCode, WindowEvent};
+use winit::event::{
+    ElementState, Event, ModifiersState, MouseButton, VirtualKeyCode, WindowEvent,
+};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::{Window, WindowBuilder};
 
@@ -23,6 +29,8 @@ enum GUIGameAction {
     Play,
     GrowCell,
     KillCell,
+    Undo,
+    Redo,
 }
 impl TryFrom<game::GameAction> for GUIGameAction {
     type Error = ();
@@ -33,54 +41,128 @@ impl TryFrom<game::GameAction> for GUIGameAction {
             game::GameAction::Paused => Ok(GUIGameAction::Paused),
             game::GameAction::Play => Ok(GUIGameAction::Play),
             game::GameAction::Step => Ok(GUIGameAction::Step),
+            game::GameAction::Undo => Ok(GUIGameAction::Undo),
+            game::GameAction::Redo => Ok(GUIGameAction::Redo),
             _ => Err(()),
         }
     }
 }
-pub(crate) struct GUIGameState {
-    pub(crate) board: game::Game,
+/// The GUI's state, w/ the board run by any engine
+pub(crate) struct GUIGameState<B: Board> {
+    pub(crate) board: B,
+    /// Makes an empty board of the engine, eg. to load a file onto
+    new_board: fn(usize, usize) -> B,
     current_action: Option<GUIGameAction>,
     timing: StepDelay,
+    /// Whether playing pauses once the board stops changing or starts repeating
+    stop_on_cycle: bool,
+    /// The last cycle that was shown, so it's only shown once
+    noticed_cycle: Option<Cycle>,
+    /// The cells picked w/ the mouse, for copying & cutting
+    selection: Option<Rect>,
+    clipboard: Option<Pattern>,
+    paste_mode: PasteMode,
+    catalogue: Catalogue,
 }
-impl GUIGameState {
-    pub(crate) fn new(size: (usize, usize)) -> Self {
-        GUIGameState {
-            board: game::Game::new(size.0, size.1),
-            current_action: None,
-            timing: StepDelay {
-                delay: std::time::Duration::from_millis(200),
-                prev_step: std::time::Instant::now(),
-            },
-        }
+impl<B: Board> GUIGameState<B> {
+    pub(crate) fn new(size: (usize, usize), new_board: fn(usize, usize) -> B) -> Self {
+        GUIGameState::from_board(new_board(size.0, size.1), new_board)
     }
-    pub(crate) fn from_game(game: game::Game) -> Self {
+    pub(crate) fn from_board(board: B, new_board: fn(usize, usize) -> B) -> Self {
         GUIGameState {
-            board: game,
+            board,
+            new_board,
             current_action: None,
             timing: StepDelay {
                 delay: std::time::Duration::from_millis(200),
                 prev_step: std::time::Instant::now(),
             },
+            stop_on_cycle: false,
+            noticed_cycle: None,
+            selection: None,
+            clipboard: None,
+            paste_mode: PasteMode::default(),
+            catalogue: Catalogue::default(),
         }
     }
     pub(crate) fn tick(&mut self) {
         self.board.step(1);
         self.timing.prev_step = std::time::Instant::now();
+
+        let cycle = self.board.cycle();
+        if let Some(c) = cycle.filter(|c| self.noticed_cycle != Some(*c)) {
+            println!("Notice: {c}");
+        }
+        self.noticed_cycle = cycle;
+    }
+    pub(crate) fn toggle_stop_on_cycle(&mut self) {
+        self.stop_on_cycle = !self.stop_on_cycle;
+        match self.stop_on_cycle {
+            true => println!("Playing will pause once the board is still or repeating"),
+            false => println!("Playing won't pause when the board is still or repeating"),
+        }
+    }
+    /// Copies the selected cells onto the clipboard, killing them if 'cut' is true
+    pub(crate) fn copy_selection(&mut self, cut: bool) {
+        let Some(area) = self.selection else {
+            println!("Nothing is selected, drag w/ the mouse to select cells");
+            return;
+        };
+        let copied = match cut {
+            true => self.board.cut(area),
+            false => self.board.copy(area),
+        };
+        match copied {
+            Ok(pattern) => {
+                println!("Copied a {} by {} region", pattern.width(), pattern.height());
+                self.clipboard = Some(pattern);
+            }
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+    /// Pastes the clipboard w/ its top left corner at 'at'
+    pub(crate) fn paste_at(&mut self, at: (usize, usize)) {
+        match &self.clipboard {
+            Some(pattern) => self.board.paste(pattern, at, self.paste_mode),
+            None => println!("Nothing has been copied!"),
+        }
     }
-    pub(crate) fn load_new_board(&mut self, new: game::Game) {
+    pub(crate) fn transform_clipboard(&mut self, transform: Transform) {
+        match &self.clipboard {
+            Some(pattern) => {
+                self.clipboard = Some(pattern.transformed(transform));
+                println!("Turned the clipboard ({transform})");
+            }
+            None => println!("Nothing has been copied!"),
+        }
+    }
+    pub(crate) fn next_paste_mode(&mut self) {
+        self.paste_mode = self.paste_mode.next();
+        println!("Pasting w/ {}", self.paste_mode);
+    }
+    pub(crate) fn load_new_board(&mut self, mut new: B) {
+        new.set_threads(self.board.threads());
         self.board = new;
     }
-    pub(crate) fn consume_current_event(&mut self) {
+    /// Handles the current action, returns true if the board might have changed
+    pub(crate) fn consume_current_event(&mut self) -> bool {
         if self.current_action.is_none() {
-            return;
+            return false;
         }
         match self.current_action.unwrap() {
             GUIGameAction::Step => self.tick(),
-            GUIGameAction::Paused => return,
+            GUIGameAction::Paused => return false,
             GUIGameAction::Play => {
-                if self.timing.can_step() {
-                    self.tick();
+                if !self.timing.can_step() {
+                    return false;
                 }
+                self.tick();
+                if self.stop_on_cycle && self.board.cycle().is_some() {
+                    println!("Paused, the board is still or repeating");
+                    self.current_action = Some(GUIGameAction::Paused);
+                }
+                // Keep playing until paused
+                return true;
             }
             GUIGameAction::GrowCell => {
                 let to_change = text::get_coordinates(&std::io::stdin());
@@ -90,8 +172,19 @@ impl GUIGameState {
                 let to_change = text::get_coordinates(&std::io::stdin());
                 self.board.set_many(&to_change, &[CellState::Dead; 1]);
             }
+            GUIGameAction::Undo => {
+                if !self.board.undo() {
+                    println!("Nothing to undo!");
+                }
+            }
+            GUIGameAction::Redo => {
+                if !self.board.redo() {
+                    println!("Nothing to redo!");
+                }
+            }
         }
         self.current_action = None;
+        true
     }
 }
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -99,6 +192,11 @@ enum ProgramEvent {
     ShowHelp,
     SaveBoard,
     LoadBoard,
+    ChangeRule,
+    ChangeTopology,
+    ResizeBoard,
+    PlacePattern,
+    RandomSoup,
     ExitApplication,
 }
 struct ProgramManager {
@@ -137,6 +235,24 @@ struct DrawInformation {
     padding: u32,
 }
 impl DrawInformation {
+    /// The size of the screen for a board
+    fn screen_size_for(
+        board: &impl Board,
+        cell_size: (u32, u32),
+        padding: u32,
+    ) -> PhysicalSize<u32> {
+        PhysicalSize::new(
+            (board.width() as u32) * (cell_size.0 + padding) + padding,
+            (board.height() as u32) * (cell_size.1 + padding) + padding,
+        )
+    }
+    /// Changes the screen size to fit the board, returns true if it changed
+    fn fit_to_board(&mut self, board: &impl Board) -> bool {
+        let size = DrawInformation::screen_size_for(board, self.cell_size, self.padding);
+        let changed = size != self.screen_size;
+        self.screen_size = size;
+        changed
+    }
     #[allow(unused)]
     fn index_to_pixel(&self, idx: usize) -> (u32, u32) {
         (
@@ -163,23 +279,37 @@ impl DrawInformation {
         );
         Some((cell_x as usize, cell_y as usize))
     }
+    /// The index of every pixel inside a cell (not counting the padding around it)
+    fn cell_pixels(&self, (x, y): (usize, usize)) -> impl Iterator<Item = usize> + '_ {
+        let (w, h) = (self.cell_size.0 + self.padding, self.cell_size.1 + self.padding);
+        let (left, top) = (x as u32 * w + self.padding + 1, y as u32 * h + self.padding + 1);
+        (top..(y as u32 + 1) * h).flat_map(move |py| {
+            (left..(x as u32 + 1) * w).map(move |px| (py * self.screen_size.width + px) as usize)
+        })
+    }
+    /// The cell under a pixel, the padding before a cell counts as part of it
+    fn pixel_to_cell(&self, x: usize, y: usize) -> (usize, usize) {
+        (
+            x / (self.cell_size.0 + self.padding) as usize,
+            y / (self.cell_size.1 + self.padding) as usize,
+        )
+    }
 }
 
 /// Entry point for GUI control and handling of the application
-/// The program will run
-pub(crate) fn gui(start: Option<game::Game>) {
-    let game = match start {
-        Some(g) => GUIGameState::from_game(g),
-        None => GUIGameState::new((GAME_X, GAME_Y)),
+/// The program will run w/ any engine, 'new_board' makes an empty board of it
+pub(crate) fn gui<B: Board + 'static>(start: Option<B>, new_board: fn(usize, usize) -> B) {
+    let mut game = match start {
+        Some(board) => GUIGameState::from_board(board, new_board),
+        None => GUIGameState::new((GAME_X, GAME_Y), new_board),
     };
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    game.board.set_threads(threads);
 
     const PIXELS_PER_CELL: (u32, u32) = (8u32, 8u32);
     const PADDING: u32 = 2u32;
     let draw_info = DrawInformation {
-        screen_size: PhysicalSize::new(
-            (game.board.x_max as u32) * (PIXELS_PER_CELL.0 + PADDING) + PADDING,
-            (game.board.y_max as u32) * (PIXELS_PER_CELL.1 + PADDING) + PADDING,
-        ),
+        screen_size: DrawInformation::screen_size_for(&game.board, PIXELS_PER_CELL, PADDING),
         cell_size: PIXELS_PER_CELL,
         padding: PADDING,
     };
@@ -192,7 +322,8 @@ pub(crate) fn gui(start: Option<game::Game>) {
     };
 
     println!("Controls:");
-    println!(" , -> Play, . -> Pause, g -> Grow, k -> Kill, = -> Step, s -> Save, h -> Help, l -> Load, q -> Quit");
+    println!(" , -> Play, . -> Pause, g -> Grow, k -> Kill, = -> Step, s -> Save, h -> Help, l -> Load, r -> Rule, t -> Topology, z -> Undo, y -> Redo, c -> Pause on cycles, b -> Resize, p -> Patterns, n -> Random soup, q -> Quit");
+    println!(" Drag the mouse to select cells (right click to unselect), ctrl + c/x/v -> Copy/Cut/Paste at the mouse, o -> Rotate, f/shift + f -> Flip, m -> Paste mode");
 
     run_gui(e, w, p, game, ProgramManager::new(), draw_info);
 }
@@ -226,14 +357,14 @@ pub(crate) fn incrementing_render() -> ! {
         cell_size: (8u32, 8u32),
         padding: 2u32,
     };
-    let mut game = GUIGameState::new((120, 80));
+    let mut game = GUIGameState::new((120, 80), game::Game::new);
     let mut place_holder = (0usize, 0usize);
 
     let (mut pixels, win, l) = gui_init(draw.screen_size);
     l.run(move |event, _, control_flow| {
         match event {
             Event::RedrawRequested(id) if id == win.id() => {
-                draw_board(&game.board, &mut pixels, &draw);
+                draw_board(&game.board, &mut pixels, &draw, None);
                 if let Err(e) = pixels.render() {
                     eprintln!("Error rendering: {:?}", e);
                 }
@@ -253,8 +384,8 @@ pub(crate) fn incrementing_render() -> ! {
                         game.board.clear();
                         game.board
                             .set(place_holder.0, place_holder.1, CellState::Alive);
-                        place_holder = if place_holder.0 + 1 >= game.board.x_max {
-                            (0, (place_holder.1 + 1) % game.board.y_max)
+                        place_holder = if place_holder.0 + 1 >= game.board.width() {
+                            (0, (place_holder.1 + 1) % game.board.height())
                         } else {
                             (place_holder.0 + 1, place_holder.1)
                         };
@@ -274,43 +405,137 @@ pub(crate) fn incrementing_render() -> ! {
     });
 }
 
-fn run_gui(
+fn run_gui<B: Board + 'static>(
     l: EventLoop<()>,
     window: Window,
     mut pixels: Pixels, // TODO: just move the code for gui_init here + idk
-    mut game: GUIGameState,
+    mut game: GUIGameState<B>,
     mut state: ProgramManager,
-    draw_info: DrawInformation,
+    mut draw_info: DrawInformation,
 ) {
+    // The cell under the mouse, & where the mouse was pressed while selecting
+    let mut cursor: Option<(usize, usize)> = None;
+    let mut dragging_from: Option<(usize, usize)> = None;
+    let mut modifiers = ModifiersState::empty();
+    // What's on the screen, so only the cells that changed need drawing again
+    let mut drawn: Option<DrawnBoard> = None;
+
     l.run(move |event, _, control_flow| match event {
         Event::MainEventsCleared => {
-            game.consume_current_event(); // handle the game events
+            // handle the game events
+            if game.consume_current_event() {
+                window.request_redraw();
+            }
 
             if let Some(e) = state.pop(){
                 match e {
                     ProgramEvent::ShowHelp => println!(
-                        "Menu: ','->Play, '.'->Pause, 'g'->Grow, 'K'->Kill, '='->Step, 'S'->Save, 'L'->Load"
+                        "Menu: ','->Play, '.'->Pause, 'g'->Grow, 'K'->Kill, '='->Step, 'S'->Save, 'L'->Load, 'R'->Rule, 'T'->Topology, 'Z'->Undo, 'Y'->Redo, 'C'->Pause on cycles, 'B'->Resize, Mouse->Select, Ctrl+'C'/'X'/'V'->Copy/Cut/Paste, 'O'->Rotate, 'F'->Flip, 'M'->Paste mode, 'P'->Patterns (placed at the mouse if there's no position), 'N'->Random soup (in the selection if there's no region)"
                     ),
                     ProgramEvent::SaveBoard => {
                         let path = text::get_file_path();
-                        match save_load::save_game(&game.board, &path) {
+                        match save_load::save_game(&game.board, path.trim()) {
                             Ok(_) => {},
                             Err(e) => eprintln!("Issue saving board: {:?}", e),
                         };
                     },
                     ProgramEvent::LoadBoard => {
                         let path = text::get_file_path();
-                        match save_load::load_game(path.trim()) {
+                        match save_load::load_padded(path.trim(), game.new_board) {
                             Ok(b) => game.load_new_board(b),
                             Err(e) => eprintln!("Couldn't load board: {:?}", e),
                         };
                     },
+                    ProgramEvent::ChangeRule => {
+                        if let Some(rule) = text::get_user_rule(&std::io::stdin()) {
+                            if let Err(e) = game.board.set_rule(rule) {
+                                eprintln!("{e}");
+                            }
+                        }
+                    },
+                    ProgramEvent::ChangeTopology => {
+                        let topology = text::get_user_topology(&std::io::stdin());
+                        if let Err(e) = game.board.set_topology(topology) {
+                            eprintln!("{e}");
+                        }
+                    },
+                    ProgramEvent::ResizeBoard => {
+                        if let Some(resize) = text::get_user_resize(&std::io::stdin()) {
+                            if let Err(e) = game.board.resize(resize) {
+                                eprintln!("{e}");
+                            }
+                            window.request_redraw();
+                        }
+                    },
+                    ProgramEvent::PlacePattern => {
+                        match text::get_user_catalogue_command(&std::io::stdin()) {
+                            Some(CatalogueCommand::Place { name, at, orientation }) => {
+                                let pattern = game.catalogue.oriented(&name, &orientation);
+                                // Without a position it goes under the mouse
+                                match (pattern, at.or(cursor)) {
+                                    (Ok(pattern), Some(at)) => {
+                                        game.board.paste(&pattern, at, game.paste_mode);
+                                        window.request_redraw();
+                                    }
+                                    (Err(e), _) => eprintln!("{e}"),
+                                    (_, None) => eprintln!("Where should it go? eg. place glider 10,10"),
+                                }
+                            }
+                            Some(command) => {
+                                text::run_catalogue_command(&mut game.catalogue, command)
+                            }
+                            None => {}
+                        }
+                    },
+                    ProgramEvent::RandomSoup => {
+                        if let Some(mut soup) = text::get_user_soup(&std::io::stdin()) {
+                            // Fills the selection, if there's one & no region was given
+                            soup.region = soup.region.or(game.selection);
+                            match game.board.apply_soup(&soup) {
+                                Ok(_) => println!("Made the soup: {soup}"),
+                                Err(e) => eprintln!("{e}"),
+                            }
+                            window.request_redraw();
+                        }
+                    },
                     ProgramEvent::ExitApplication => *control_flow = ControlFlow::Exit,
                 }
             }
         }
         Event::RedrawRequested(id) if window.id() == id => {
-            draw_board(&game.board, &mut pixels, &draw_info);
+            // The board changes size when it's resized or a new one is loaded
+            let resized = draw_info.fit_to_board(&game.board);
+            if resized {
+                fit_window(&window, &mut pixels, &draw_info);
+            }
+            let states = game.board.rule().states();
+            let (generation, edits) = (game.board.generation(), game.board.edits());
+            // W/o edits since the last frame, only the cells that changed in the
+            // generation since then need drawing (& none if it didn't step). Engines
+            // that don't keep the changed cells are drawn in full
+            let changes = drawn
+                .as_ref()
+                .filter(|d| !resized && d.selection == game.selection && d.states == states)
+                .filter(|d| d.edits == edits)
+                .zip(game.board.changed_cells())
+                .and_then(|(d, changed)| match generation.checked_sub(d.generation) {
+                    Some(0) => Some(Vec::new()),
+                    Some(1) => Some(changed),
+                    _ => None,
+                });
+            match changes {
+                Some(cells) => {
+                    draw_changes(&game.board, &cells, &mut pixels, &draw_info, game.selection)
+                }
+                None => draw_board(&game.board, &mut pixels, &draw_info, game.selection),
+            }
+            drawn = Some(DrawnBoard {
+                generation,
+                edits,
+                selection: game.selection,
+                states,
+            });
+            window.set_title(&format!("Game of Life - {}", game.board.stats()));
             match pixels.render(){
                 Ok(_) => {},
                 Err(e) => eprintln!("Error Rendering with Pixels: {e}"),
@@ -318,6 +543,30 @@ fn run_gui(
         }
         Event::WindowEvent { window_id, event } if window_id == window.id() => match event {
             WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+            WindowEvent::ModifiersChanged(m) => modifiers = m,
+            WindowEvent::CursorMoved { position, .. } => {
+                cursor = pixels
+                    .window_pos_to_pixel((position.x as f32, position.y as f32))
+                    .ok()
+                    .map(|(x, y)| draw_info.pixel_to_cell(x, y))
+                    .filter(|(x, y)| *x < game.board.width() && *y < game.board.height());
+                if let (Some(start), Some(end)) = (dragging_from, cursor) {
+                    game.selection = Some(Rect::from_corners(start, end));
+                    window.request_redraw();
+                }
+            }
+            WindowEvent::MouseInput { state: pressed, button, .. } => {
+                match (button, pressed) {
+                    (MouseButton::Left, ElementState::Pressed) => {
+                        dragging_from = cursor;
+                        game.selection = cursor.map(|c| Rect::from_corners(c, c));
+                    }
+                    (MouseButton::Left, ElementState::Released) => dragging_from = None,
+                    (MouseButton::Right, ElementState::Pressed) => game.selection = None,
+                    _ => {}
+                }
+                window.request_redraw();
+            }
             WindowEvent::KeyboardInput { input, .. } if input.virtual_keycode.is_some() => {
                 // prevent double pressing, NOTE: this prob needs to be changed later !!!
                 if input.state == ElementState::Released {
@@ -329,10 +578,33 @@ fn run_gui(
                     VirtualKeyCode::G => game.current_action = Some(GUIGameAction::GrowCell),
                     VirtualKeyCode::K => game.current_action = Some(GUIGameAction::KillCell),
                     VirtualKeyCode::Equals => game.current_action = Some(GUIGameAction::Step),
+                    VirtualKeyCode::Z => game.current_action = Some(GUIGameAction::Undo),
+                    VirtualKeyCode::Y => game.current_action = Some(GUIGameAction::Redo),
+                    VirtualKeyCode::C if modifiers.ctrl() => game.copy_selection(false),
+                    VirtualKeyCode::X if modifiers.ctrl() => game.copy_selection(true),
+                    VirtualKeyCode::V if modifiers.ctrl() => {
+                        // Under the mouse, or over the selection if the mouse is off the board
+                        match cursor.or(game.selection.map(|s| (s.x, s.y))) {
+                            Some(at) => game.paste_at(at),
+                            None => println!("Move the mouse to where it should be pasted"),
+                        }
+                    }
+                    VirtualKeyCode::C => game.toggle_stop_on_cycle(),
+                    VirtualKeyCode::O => game.transform_clipboard(Transform::Rotate90),
+                    VirtualKeyCode::F if modifiers.shift() => {
+                        game.transform_clipboard(Transform::FlipVertical)
+                    }
+                    VirtualKeyCode::F => game.transform_clipboard(Transform::FlipHorizontal),
+                    VirtualKeyCode::M => game.next_paste_mode(),
 
                     VirtualKeyCode::S => state.add_event_ignore(ProgramEvent::SaveBoard),
                     VirtualKeyCode::H => state.add_event_ignore(ProgramEvent::ShowHelp),
                     VirtualKeyCode::L => state.add_event_ignore(ProgramEvent::LoadBoard),
+                    VirtualKeyCode::R => state.add_event_ignore(ProgramEvent::ChangeRule),
+                    VirtualKeyCode::T => state.add_event_ignore(ProgramEvent::ChangeTopology),
+                    VirtualKeyCode::B => state.add_event_ignore(ProgramEvent::ResizeBoard),
+                    VirtualKeyCode::P => state.add_event_ignore(ProgramEvent::PlacePattern),
+                    VirtualKeyCode::N => state.add_event_ignore(ProgramEvent::RandomSoup),
                     VirtualKeyCode::Q => state.add_event_ignore(ProgramEvent::ExitApplication),
 
                     VirtualKeyCode::Escape => *control_flow = ControlFlow::Exit,
@@ -345,6 +617,19 @@ fn run_gui(
         _ => {}
     });
 }
+/// Resizes the window & pixel buffer to the screen size, and redraws the grid
+fn fit_window(window: &Window, pixels: &mut Pixels, info: &DrawInformation) {
+    let size = info.screen_size;
+    window.set_min_inner_size(Some(size));
+    window.set_inner_size(size);
+    if let Err(e) = pixels.resize_surface(size.width, size.height) {
+        eprintln!("Error resizing the window: {e}");
+    }
+    if let Err(e) = pixels.resize_buffer(size.width, size.height) {
+        eprintln!("Error resizing the window: {e}");
+    }
+    initial_gui_draw(pixels, info);
+}
 fn initial_gui_draw(pixels: &mut Pixels, info: &DrawInformation) {
     for (idx, pixel) in pixels.frame_mut().chunks_exact_mut(4).enumerate() {
         let color = match info.index_to_cell(idx) {
@@ -356,17 +641,71 @@ fn initial_gui_draw(pixels: &mut Pixels, info: &DrawInformation) {
     }
 }
 
-#[warn(incomplete_features)]
-fn draw_board(board: &game::Game, pixels: &mut Pixels, draw_info: &DrawInformation) {
-    const BLACK: [u8; 4] = [0; 4];
-    const WHITE: [u8; 4] = [200; 4];
+const BLACK: [u8; 4] = [0; 4];
+const WHITE: [u8; 4] = [200; 4];
+
+/// The colour of a selected cell, the cell's colour tinted blue
+fn selected_color(color: [u8; 4]) -> [u8; 4] {
+    [color[0] / 2, color[1] / 2, color[2] / 2 + 100, 255]
+}
+/// The colour of a cell, dying cells fade from orange to dark red as they get
+/// closer to being dead
+fn cell_color(cell: CellState, states: u8) -> [u8; 4] {
+    match cell {
+        CellState::Alive => WHITE,
+        CellState::Dead => BLACK,
+        CellState::Dying(left) => {
+            let shade = (40 + 160 * left as u32 / (states as u32 - 1).max(1)) as u8;
+            [shade, shade / 3, 0, shade]
+        }
+    }
+}
+
+/// What the board was like when it was last drawn
+struct DrawnBoard {
+    generation: u64,
+    /// See [Board::edits]
+    edits: u64,
+    selection: Option<Rect>,
+    states: u8,
+}
+/// Redraws only the given cells, the rest of the screen is left as it was
+fn draw_changes(
+    board: &impl Board,
+    cells: &[(usize, usize)],
+    pixels: &mut Pixels,
+    draw_info: &DrawInformation,
+    selection: Option<Rect>,
+) {
+    let states = board.rule().states();
+    let frame = pixels.frame_mut();
+    for &(x, y) in cells {
+        let cell = cell_color(board.get(x, y).unwrap_or(CellState::Dead), states);
+        let color = match selection {
+            Some(s) if s.contains(x, y) => selected_color(cell),
+            _ => cell,
+        };
+        for idx in draw_info.cell_pixels((x, y)) {
+            frame[idx * 4..idx * 4 + 4].copy_from_slice(&color);
+        }
+    }
+}
 
+#[warn(incomplete_features)]
+fn draw_board(
+    board: &impl Board,
+    pixels: &mut Pixels,
+    draw_info: &DrawInformation,
+    selection: Option<Rect>,
+) {
+    let states = board.rule().states();
     for (idx, pixel) in pixels.frame_mut().chunks_exact_mut(4).enumerate() {
         if let Some((x, y)) = draw_info.index_to_cell(idx) {
-            match board[(x, y)] {
-                CellState::Alive => pixel.copy_from_slice(&WHITE),
-                CellState::Dead => pixel.copy_from_slice(&BLACK),
-            };
+            let color = cell_color(board.get(x, y).unwrap_or(CellState::Dead), states);
+            match selection {
+                Some(s) if s.contains(x, y) => pixel.copy_from_slice(&selected_color(color)),
+                _ => pixel.copy_from_slice(&color),
+            }
             continue;
         }
 
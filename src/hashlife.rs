@@ -0,0 +1,688 @@
+use std::collections::HashMap;
+
+use crate::game::{CellState, Game};
+use crate::rule::Rule;
+
+type NodeId = usize;
+
+/// A square of 2^level by 2^level cells, made out of 4 quadrants one level down.
+/// Level 0 nodes are single cells, and have no quadrants.
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    nw: NodeId,
+    ne: NodeId,
+    sw: NodeId,
+    se: NodeId,
+    level: u8,
+    population: u64,
+}
+
+const DEAD_LEAF: NodeId = 0;
+const ALIVE_LEAF: NodeId = 1;
+/// Macrocell files write squares this level (8 by 8) & smaller as rows of cells
+const MACROCELL_LEAF_LEVEL: u8 = 3;
+/// The biggest square a macrocell file can have, so every cell has an i64 position
+const MACROCELL_MAX_LEVEL: u8 = 62;
+
+/// The HashLife algorithm: the (infinite) board is a quadtree where identical squares
+/// are only ever stored once, and the future of every square is remembered once it
+/// has been worked out. Repetitive patterns (guns, breeders, etc.) can be run for
+/// huge numbers of generations this way, see [HashLife::step_pow2].
+///
+/// Like [crate::sparse::SparseGame], the board has no edges & uses signed coordinates.
+#[allow(unused)]
+pub struct HashLife {
+    nodes: Vec<Node>,
+    lookup: HashMap<[NodeId; 4], NodeId>,
+    /// (node, j) -> the center of the node, 2^j generations later
+    results: HashMap<(NodeId, u8), NodeId>,
+    /// An empty node for each level
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// The position of the root's top left corner
+    origin: (i64, i64),
+    generation: u64,
+    rule: Rule,
+}
+#[allow(unused)]
+impl HashLife {
+    pub fn new(rule: Rule) -> Result<Self, &'static str> {
+        if rule.next_state(CellState::Dead, 0) == CellState::Alive {
+            return Err("B0 rules can't be run with HashLife");
+        }
+        if !rule.is_life_like() {
+            return Err("HashLife only runs totalistic 2 state rules on the 8 nearest neighbors");
+        }
+        let leaf = |population| Node {
+            nw: DEAD_LEAF,
+            ne: DEAD_LEAF,
+            sw: DEAD_LEAF,
+            se: DEAD_LEAF,
+            level: 0,
+            population,
+        };
+        let mut life = HashLife {
+            nodes: vec![leaf(0), leaf(1)],
+            lookup: HashMap::new(),
+            results: HashMap::new(),
+            empty: vec![DEAD_LEAF],
+            root: DEAD_LEAF,
+            origin: (0, 0),
+            generation: 0,
+            rule,
+        };
+        life.root = life.empty_node(3);
+        Ok(life)
+    }
+    /// Copies the living cells of a board, with the board's (0, 0) at (0, 0)
+    pub fn from_game(game: &Game) -> Result<Self, &'static str> {
+        let mut life = HashLife::new(game.rule())?;
+        let size = game.x_max.max(game.y_max).max(8);
+        let level = size.next_power_of_two().trailing_zeros() as u8;
+        let rows: Vec<&[CellState]> = game.rows().collect();
+        life.root = life.build(level, 0, 0, &|x, y| match rows.get(y) {
+            Some(row) if x < row.len() => row[x],
+            _ => CellState::Dead,
+        });
+        Ok(life)
+    }
+    fn build(
+        &mut self,
+        level: u8,
+        x: usize,
+        y: usize,
+        cell: &dyn Fn(usize, usize) -> CellState,
+    ) -> NodeId {
+        if level == 0 {
+            return match cell(x, y) {
+                CellState::Alive => ALIVE_LEAF,
+                CellState::Dead | CellState::Dying(_) => DEAD_LEAF,
+            };
+        }
+        let half = 1 << (level - 1);
+        let nw = self.build(level - 1, x, y, cell);
+        let ne = self.build(level - 1, x + half, y, cell);
+        let sw = self.build(level - 1, x, y + half, cell);
+        let se = self.build(level - 1, x + half, y + half, cell);
+        self.join(nw, ne, sw, se)
+    }
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// Changes the rule, see [HashLife::new] for the rules that can be run. Everything
+    /// remembered under the old rule is dropped, the board itself is kept
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        let mut life = HashLife::new(rule)?;
+        if let Some(((x0, y0), (x1, y1))) = self.bounding_box() {
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    if self.get(x, y) == CellState::Alive {
+                        life.set(x, y, CellState::Alive);
+                    }
+                }
+            }
+        }
+        life.generation = self.generation;
+        *self = life;
+        Ok(())
+    }
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+    /// How many distinct squares are stored, useful for keeping an eye on memory use
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Forgets every remembered result, the board itself is kept
+    pub fn clear_cache(&mut self) {
+        self.results.clear();
+    }
+
+    /// Finds or creates the node with the given quadrants
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        if let Some(id) = self.lookup.get(&[nw, ne, sw, se]) {
+            return *id;
+        }
+        let population = [nw, ne, sw, se]
+            .iter()
+            .fold(0u64, |sum, n| sum.saturating_add(self.nodes[*n].population));
+        self.nodes.push(Node {
+            nw,
+            ne,
+            sw,
+            se,
+            level: self.nodes[nw].level + 1,
+            population,
+        });
+        let id = self.nodes.len() - 1;
+        self.lookup.insert([nw, ne, sw, se], id);
+        id
+    }
+    fn empty_node(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let bigger = self.join(e, e, e, e);
+            self.empty.push(bigger);
+        }
+        self.empty[level as usize]
+    }
+    /// The node one level down, made from the middle of the given one
+    fn center(&mut self, id: NodeId) -> NodeId {
+        let n = self.nodes[id];
+        let (nw, ne, sw, se) = (
+            self.nodes[n.nw],
+            self.nodes[n.ne],
+            self.nodes[n.sw],
+            self.nodes[n.se],
+        );
+        self.join(nw.se, ne.sw, sw.ne, se.nw)
+    }
+    /// Puts the root in the middle of a node twice its size
+    fn expand(&mut self) {
+        let root = self.nodes[self.root];
+        let e = self.empty_node(root.level - 1);
+        let nw = self.join(e, e, e, root.nw);
+        let ne = self.join(e, e, root.ne, e);
+        let sw = self.join(e, root.sw, e, e);
+        let se = self.join(root.se, e, e, e);
+        self.root = self.join(nw, ne, sw, se);
+
+        let shift = 1i64 << (root.level - 1);
+        self.origin = (self.origin.0 - shift, self.origin.1 - shift);
+    }
+    /// Whether every living cell of the root is in its middle half
+    fn root_is_padded(&mut self) -> bool {
+        let c = self.center(self.root);
+        self.nodes[c].population == self.population()
+    }
+    /// Shrinks the root down, as long as nothing alive is lost
+    fn shrink(&mut self) {
+        while self.nodes[self.root].level > 3 && self.root_is_padded() {
+            let shift = 1i64 << (self.nodes[self.root].level - 2);
+            self.root = self.center(self.root);
+            self.origin = (self.origin.0 + shift, self.origin.1 + shift);
+        }
+    }
+
+    /// Works out the middle 2x2 cells of a 4x4 node, one generation later
+    fn step_4x4(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[CellState::Dead; 4]; 4];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_in(id, x as i64, y as i64);
+            }
+        }
+        let mut next = [DEAD_LEAF; 4];
+        for (i, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let alive_in_3x3 = cells[y - 1..=y + 1]
+                .iter()
+                .flat_map(|row| &row[x - 1..=x + 1])
+                .filter(|c| **c == CellState::Alive)
+                .count();
+            let alive_neighbors = alive_in_3x3 - (cells[y][x] == CellState::Alive) as usize;
+            if self.rule.next_state(cells[y][x], alive_neighbors) == CellState::Alive {
+                next[i] = ALIVE_LEAF;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+    /// The middle of the given node (level k), 2^j generations later (j <= k - 2)
+    fn successor(&mut self, id: NodeId, j: u8) -> NodeId {
+        let n = self.nodes[id];
+        let j = j.min(n.level - 2);
+        if n.population == 0 {
+            return self.empty_node(n.level - 1);
+        }
+        if let Some(result) = self.results.get(&(id, j)) {
+            return *result;
+        }
+        let result = if n.level == 2 {
+            self.step_4x4(id)
+        } else {
+            let (nw, ne, sw, se) = (
+                self.nodes[n.nw],
+                self.nodes[n.ne],
+                self.nodes[n.sw],
+                self.nodes[n.se],
+            );
+            // The 9 overlapping squares that are half the size of this one
+            let squares = [
+                n.nw,
+                self.join(nw.ne, ne.nw, nw.se, ne.sw),
+                n.ne,
+                self.join(nw.sw, nw.se, sw.nw, sw.ne),
+                self.join(nw.se, ne.sw, sw.ne, se.nw),
+                self.join(ne.sw, ne.se, se.nw, se.ne),
+                n.sw,
+                self.join(sw.ne, se.nw, sw.se, se.sw),
+                n.se,
+            ];
+            let mut c = [DEAD_LEAF; 9];
+            for (i, square) in squares.into_iter().enumerate() {
+                c[i] = self.successor(square, j);
+            }
+
+            if j < n.level - 2 {
+                // Only part of the time is needed, so just stitch the middles together
+                let q: Vec<Node> = c.iter().map(|id| self.nodes[*id]).collect();
+                let nw = self.join(q[0].se, q[1].sw, q[3].ne, q[4].nw);
+                let ne = self.join(q[1].se, q[2].sw, q[4].ne, q[5].nw);
+                let sw = self.join(q[3].se, q[4].sw, q[6].ne, q[7].nw);
+                let se = self.join(q[4].se, q[5].sw, q[7].ne, q[8].nw);
+                self.join(nw, ne, sw, se)
+            } else {
+                // Full speed, run the 4 quadrants forward again
+                let nw = self.join(c[0], c[1], c[3], c[4]);
+                let ne = self.join(c[1], c[2], c[4], c[5]);
+                let sw = self.join(c[3], c[4], c[6], c[7]);
+                let se = self.join(c[4], c[5], c[7], c[8]);
+                let nw = self.successor(nw, j);
+                let ne = self.successor(ne, j);
+                let sw = self.successor(sw, j);
+                let se = self.successor(se, j);
+                self.join(nw, ne, sw, se)
+            }
+        };
+        self.results.insert((id, j), result);
+        result
+    }
+
+    /// Runs the board forward 2^k generations in one go
+    pub fn step_pow2(&mut self, k: u8) {
+        while self.nodes[self.root].level < k + 2 || !self.root_is_padded() {
+            self.expand();
+        }
+        // One more level, so nothing can grow out of the result
+        self.expand();
+
+        let shift = 1i64 << (self.nodes[self.root].level - 2);
+        self.root = self.successor(self.root, k);
+        self.origin = (self.origin.0 + shift, self.origin.1 + shift);
+        self.generation += 1 << k;
+        self.shrink();
+    }
+    /// Runs the board forward any number of generations, in power of 2 jumps
+    pub fn step(&mut self, steps: u64) {
+        for k in 0..64 {
+            if steps & (1 << k) != 0 {
+                self.step_pow2(k);
+            }
+        }
+    }
+
+    /// Gets a cell relative to the top left corner of a node
+    fn get_in(&self, mut id: NodeId, mut x: i64, mut y: i64) -> CellState {
+        loop {
+            let n = self.nodes[id];
+            if n.population == 0 {
+                return CellState::Dead;
+            }
+            if n.level == 0 {
+                return CellState::Alive;
+            }
+            let half = 1i64 << (n.level - 1);
+            id = match (x >= half, y >= half) {
+                (false, false) => n.nw,
+                (true, false) => n.ne,
+                (false, true) => n.sw,
+                (true, true) => n.se,
+            };
+            x %= half;
+            y %= half;
+        }
+    }
+    fn contains(&self, x: i64, y: i64) -> bool {
+        let size = 1i64 << self.nodes[self.root].level;
+        (self.origin.0..self.origin.0 + size).contains(&x)
+            && (self.origin.1..self.origin.1 + size).contains(&y)
+    }
+    pub fn get(&self, x: i64, y: i64) -> CellState {
+        if !self.contains(x, y) {
+            return CellState::Dead;
+        }
+        self.get_in(self.root, x - self.origin.0, y - self.origin.1)
+    }
+    fn set_in(&mut self, id: NodeId, x: i64, y: i64, cell: CellState) -> NodeId {
+        let n = self.nodes[id];
+        if n.level == 0 {
+            return match cell {
+                CellState::Alive => ALIVE_LEAF,
+                CellState::Dead | CellState::Dying(_) => DEAD_LEAF,
+            };
+        }
+        let half = 1i64 << (n.level - 1);
+        let (mut nw, mut ne, mut sw, mut se) = (n.nw, n.ne, n.sw, n.se);
+        match (x >= half, y >= half) {
+            (false, false) => nw = self.set_in(nw, x, y, cell),
+            (true, false) => ne = self.set_in(ne, x - half, y, cell),
+            (false, true) => sw = self.set_in(sw, x, y - half, cell),
+            (true, true) => se = self.set_in(se, x - half, y - half, cell),
+        }
+        self.join(nw, ne, sw, se)
+    }
+    pub fn set(&mut self, x: i64, y: i64, cell: CellState) {
+        while !self.contains(x, y) {
+            self.expand();
+        }
+        self.root = self.set_in(self.root, x - self.origin.0, y - self.origin.1, cell);
+    }
+
+    /// The smallest rectangle holding every living cell, as ((min x, min y), (max x, max y))
+    /// (both inclusive). None if nothing is alive
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        let [x0, y0, x1, y1] = self.extent(self.root, &mut HashMap::new())?;
+        let (ox, oy) = self.origin;
+        Some(((ox + x0, oy + y0), (ox + x1, oy + y1)))
+    }
+    /// The bounding box of a node, relative to its top left corner
+    fn extent(&self, id: NodeId, memo: &mut HashMap<NodeId, Option<[i64; 4]>>) -> Option<[i64; 4]> {
+        let n = self.nodes[id];
+        if n.population == 0 {
+            return None;
+        }
+        if n.level == 0 {
+            return Some([0; 4]);
+        }
+        if let Some(e) = memo.get(&id) {
+            return *e;
+        }
+        let half = 1i64 << (n.level - 1);
+        let quadrants = [
+            (n.nw, 0, 0),
+            (n.ne, half, 0),
+            (n.sw, 0, half),
+            (n.se, half, half),
+        ];
+        let mut result: Option<[i64; 4]> = None;
+        for (q, dx, dy) in quadrants {
+            if let Some([x0, y0, x1, y1]) = self.extent(q, memo) {
+                let e = [x0 + dx, y0 + dy, x1 + dx, y1 + dy];
+                result = Some(match result {
+                    None => e,
+                    Some(r) => [
+                        r[0].min(e[0]),
+                        r[1].min(e[1]),
+                        r[2].max(e[2]),
+                        r[3].max(e[3]),
+                    ],
+                });
+            }
+        }
+        memo.insert(id, result);
+        result
+    }
+
+    /// Reads the cells, rule & generation of a Golly macrocell file, eg.
+    /// ```text
+    /// [M2] (golly 4.2)
+    /// #R B3/S23
+    /// #G 8
+    /// .*$..*$***$
+    /// 4 1 0 0 0
+    /// ```
+    /// Every line after the '#' lines is a square, numbered from 1. 8 by 8 squares are
+    /// rows of '.' & '*' ending in '$', bigger ones are "level nw ne sw se" (2^level
+    /// cells wide, w/ the numbers of its quadrants or 0 for empty ones). The last one
+    /// is the whole board, w/ its middle at (0, 0). Other '#' lines are skipped
+    pub fn from_macrocell(contents: &str) -> Result<Self, &'static str> {
+        let lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('['));
+        let (header, squares): (Vec<&str>, Vec<&str>) = lines.partition(|l| l.starts_with('#'));
+        let mut rule = Rule::default();
+        let mut generation = 0;
+        for line in header {
+            if let Some(r) = line.strip_prefix("#R") {
+                rule = r
+                    .parse()
+                    .map_err(|_| "Couldn't read the macrocell's rule")?;
+            } else if let Some(g) = line.strip_prefix("#G") {
+                generation = g
+                    .trim()
+                    .parse()
+                    .map_err(|_| "Couldn't read the macrocell's generation")?;
+            }
+        }
+
+        let mut life = HashLife::new(rule)?;
+        life.generation = generation;
+        // The node for each square, [0] standing in for empty ones
+        let mut nodes: Vec<Option<NodeId>> = vec![None];
+        for line in squares {
+            let node = match line.starts_with(['.', '*', '$']) {
+                true => life.read_leaf(line)?,
+                false => life.read_square(line, &nodes)?,
+            };
+            nodes.push(Some(node));
+        }
+        if let Some(Some(root)) = nodes.last() {
+            let half = 1i64 << (life.nodes[*root].level - 1);
+            life.root = *root;
+            life.origin = (-half, -half);
+            life.shrink();
+        }
+        Ok(life)
+    }
+    /// An 8 by 8 square from a macrocell file, eg. ".*$..*$***$"
+    fn read_leaf(&mut self, line: &str) -> Result<NodeId, &'static str> {
+        let mut cells = [[CellState::Dead; 8]; 8];
+        let (mut x, mut y) = (0, 0);
+        for c in line.chars() {
+            match c {
+                '$' => (x, y) = (0, y + 1),
+                '.' | '*' if x < 8 && y < 8 => {
+                    if c == '*' {
+                        cells[y][x] = CellState::Alive;
+                    }
+                    x += 1;
+                }
+                _ => return Err("A macrocell square has cells outside of it"),
+            }
+        }
+        Ok(self.build(MACROCELL_LEAF_LEVEL, 0, 0, &|x, y| cells[y][x]))
+    }
+    /// A square made of 4 earlier ones from a macrocell file, eg. "4 1 0 0 2"
+    fn read_square(
+        &mut self,
+        line: &str,
+        nodes: &[Option<NodeId>],
+    ) -> Result<NodeId, &'static str> {
+        const INVALID: &str = "Expected a macrocell square to be \"level nw ne sw se\"";
+        let numbers = line
+            .split_whitespace()
+            .map(str::parse::<usize>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| INVALID)?;
+        let [level, nw, ne, sw, se] = numbers[..] else {
+            return Err(INVALID);
+        };
+        if !(MACROCELL_LEAF_LEVEL as usize + 1..=MACROCELL_MAX_LEVEL as usize).contains(&level) {
+            return Err("A macrocell square is too big or too small");
+        }
+        let level = level as u8;
+        let mut children = [DEAD_LEAF; 4];
+        for (child, n) in children.iter_mut().zip([nw, ne, sw, se]) {
+            *child = match nodes.get(n) {
+                Some(None) => self.empty_node(level - 1),
+                Some(Some(id)) if self.nodes[*id].level == level - 1 => *id,
+                Some(Some(_)) => return Err("A macrocell square's quadrants are the wrong size"),
+                None => return Err("A macrocell square uses one that comes after it"),
+            };
+        }
+        let [nw, ne, sw, se] = children;
+        Ok(self.join(nw, ne, sw, se))
+    }
+    /// Writes the rule, generation & cells as the lines of a macrocell file after the
+    /// "[M2]" line (see [HashLife::from_macrocell]). The board's (0, 0) stays where it is
+    pub fn to_macrocell(&self) -> String {
+        let mut contents = format!("#R {}\n#G {}\n", self.rule, self.generation);
+        let mut life = HashLife {
+            nodes: self.nodes.clone(),
+            lookup: self.lookup.clone(),
+            results: HashMap::new(),
+            empty: self.empty.clone(),
+            root: self.root,
+            origin: self.origin,
+            generation: self.generation,
+            rule: self.rule,
+        };
+        // The square written has to have its middle at (0, 0), so find the smallest
+        // one around the root
+        let root_level = life.nodes[life.root].level;
+        let (x, y) = life.origin;
+        let size = 1i64 << root_level;
+        let mut level = root_level;
+        while level < MACROCELL_MAX_LEVEL - 1 && {
+            let half = 1i64 << (level - 1);
+            x < -half || y < -half || x + size > half || y + size > half
+        } {
+            level += 1;
+        }
+        // Grow the root so its quadrants are that size, then cut the square out of them
+        while life.nodes[life.root].level <= level {
+            life.expand();
+        }
+        let r = life.nodes[life.root];
+        let half = 1i64 << (level - 1);
+        let offset = (-half - life.origin.0, -half - life.origin.1);
+        let root = life.shifted([r.nw, r.ne, r.sw, r.se], offset, &mut HashMap::new());
+        (life.root, life.origin) = (root, (-half, -half));
+
+        if life.population() > 0 {
+            life.write_squares(life.root, &mut HashMap::new(), &mut contents);
+        }
+        contents
+    }
+    /// The square the size of one of 'quadrants', 'offset' from the top left of all 4
+    /// of them (put together in a square twice the size). Offsets are the same for
+    /// every square of a level, so they aren't part of 'memo'
+    fn shifted(
+        &mut self,
+        quadrants: [NodeId; 4],
+        (dx, dy): (i64, i64),
+        memo: &mut HashMap<[NodeId; 4], NodeId>,
+    ) -> NodeId {
+        let level = self.nodes[quadrants[0]].level;
+        if (dx, dy) == (0, 0) {
+            return quadrants[0];
+        }
+        if quadrants.iter().all(|q| self.nodes[*q].population == 0) {
+            return self.empty_node(level);
+        }
+        if let Some(id) = memo.get(&quadrants) {
+            return *id;
+        }
+        // The 4 by 4 grid of the quadrants' quadrants
+        let [nw, ne, sw, se] = quadrants.map(|q| self.nodes[q]);
+        let grid = [
+            [nw.nw, nw.ne, ne.nw, ne.ne],
+            [nw.sw, nw.se, ne.sw, ne.se],
+            [sw.nw, sw.ne, se.nw, se.ne],
+            [sw.sw, sw.se, se.sw, se.se],
+        ];
+        let half = 1i64 << (level - 1);
+        let (column, row) = ((dx / half) as usize, (dy / half) as usize);
+        let offset = (dx % half, dy % half);
+        let mut square = |r: usize, c: usize| {
+            let q = [
+                grid[r][c],
+                grid[r][c + 1],
+                grid[r + 1][c],
+                grid[r + 1][c + 1],
+            ];
+            self.shifted(q, offset, memo)
+        };
+        let nw = square(row, column);
+        let ne = square(row, column + 1);
+        let sw = square(row + 1, column);
+        let se = square(row + 1, column + 1);
+        let id = self.join(nw, ne, sw, se);
+        memo.insert(quadrants, id);
+        id
+    }
+    /// Writes a square (after its quadrants), returning its number in the file
+    fn write_squares(
+        &self,
+        id: NodeId,
+        numbers: &mut HashMap<NodeId, usize>,
+        contents: &mut String,
+    ) -> usize {
+        let n = self.nodes[id];
+        if n.population == 0 {
+            return 0;
+        }
+        if let Some(number) = numbers.get(&id) {
+            return *number;
+        }
+        if n.level == MACROCELL_LEAF_LEVEL {
+            for y in 0..8 {
+                let row: String = (0..8)
+                    .map(|x| match self.get_in(id, x, y) {
+                        CellState::Alive => '*',
+                        _ => '.',
+                    })
+                    .collect();
+                contents.push_str(row.trim_end_matches('.'));
+                contents.push('$');
+            }
+            // Empty rows at the bottom are left off
+            let end = contents.trim_end_matches('$').len();
+            contents.truncate(end + 1);
+        } else {
+            let quadrants =
+                [n.nw, n.ne, n.sw, n.se].map(|q| self.write_squares(q, numbers, contents));
+            let [nw, ne, sw, se] = quadrants;
+            contents.push_str(&format!("{} {nw} {ne} {sw} {se}", n.level));
+        }
+        contents.push('\n');
+        let number = numbers.len() + 1;
+        numbers.insert(id, number);
+        number
+    }
+
+    /// Copies a viewport of the board into a [Game], with 'origin' becoming (0, 0)
+    pub fn to_game(&self, origin: (i64, i64), width: usize, height: usize) -> Game {
+        let mut game = Game::new(width, height);
+        game.set_rule(self.rule);
+        let mut cells = vec![CellState::Dead; width * height];
+        let viewport = (origin, (origin.0 + width as i64, origin.1 + height as i64));
+        self.fill(self.root, self.origin, viewport, width, &mut cells);
+        game.replace_buffer(cells).unwrap();
+        game
+    }
+    fn fill(
+        &self,
+        id: NodeId,
+        corner: (i64, i64),
+        viewport: ((i64, i64), (i64, i64)),
+        width: usize,
+        cells: &mut [CellState],
+    ) {
+        let n = self.nodes[id];
+        let size = 1i64 << n.level;
+        let ((vx0, vy0), (vx1, vy1)) = viewport;
+        if n.population == 0
+            || corner.0 >= vx1
+            || corner.1 >= vy1
+            || corner.0 + size <= vx0
+            || corner.1 + size <= vy0
+        {
+            return;
+        }
+        if n.level == 0 {
+            let (x, y) = ((corner.0 - vx0) as usize, (corner.1 - vy0) as usize);
+            cells[y * width + x] = CellState::Alive;
+            return;
+        }
+        let half = size / 2;
+        let (x, y) = corner;
+        self.fill(n.nw, (x, y), viewport, width, cells);
+        self.fill(n.ne, (x + half, y), viewport, width, cells);
+        self.fill(n.sw, (x, y + half), viewport, width, cells);
+        self.fill(n.se, (x + half, y + half), viewport, width, cells);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+
+use crate::diff::Diff;
+use crate::game::CellState;
+
+/// How many changes are kept by default
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+/// The most cells the history will hold on to (across every change), so big boards
+/// don't keep hundreds of copies of themselves around
+const MAX_STORED_CELLS: usize = 1 << 22;
+
+/// One change to a board, as either the cells that changed or (when most of the
+/// board changed) a copy of the whole board before & after
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    Cells(Diff),
+    Keyframe {
+        before: Vec<CellState>,
+        after: Vec<CellState>,
+    },
+}
+impl Change {
+    /// The change between 2 boards, only the cells at 'indexes' are looked at
+    fn between(
+        before: &[CellState],
+        after: &[CellState],
+        indexes: impl Iterator<Item = usize>,
+    ) -> Self {
+        let diff = Diff::between_indexes(before, after, indexes);
+        // each changed cell takes about as much room as 4 cells in a keyframe
+        match diff.len() {
+            n if n * 4 > before.len() => Change::Keyframe {
+                before: before.to_vec(),
+                after: after.to_vec(),
+            },
+            _ => Change::Cells(diff),
+        }
+    }
+    /// Roughly how many cells worth of memory this takes
+    fn stored_cells(&self) -> usize {
+        match self {
+            Change::Cells(diff) => diff.len() * 4 + 1,
+            Change::Keyframe { before, after } => before.len() + after.len(),
+        }
+    }
+    fn undo(&self, cells: &mut [CellState]) {
+        match self {
+            Change::Cells(diff) => diff.revert(cells).expect("the board changed size"),
+            Change::Keyframe { before, .. } => cells.copy_from_slice(before),
+        }
+    }
+    fn redo(&self, cells: &mut [CellState]) {
+        match self {
+            Change::Cells(diff) => diff.apply(cells).expect("the board changed size"),
+            Change::Keyframe { after, .. } => cells.copy_from_slice(after),
+        }
+    }
+}
+
+/// A bounded undo/redo history for a board, stored as a flat buffer of cells.
+///
+/// Every change (an edit or a generation) is recorded by comparing the cells before &
+/// after it. Once there are more than [History::limit] changes the oldest are dropped.
+#[derive(Debug, Clone)]
+pub struct History {
+    /// Each change w/ how many generations it stepped the board (0 for edits)
+    undo: VecDeque<(Change, u64)>,
+    redo: Vec<(Change, u64)>,
+    limit: usize,
+    stored: usize,
+}
+impl History {
+    pub fn new(limit: usize) -> Self {
+        History {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            stored: 0,
+        }
+    }
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+    /// Changes how many changes are kept, 0 turns the history off
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+    /// Forgets every change, eg. when the board changes size & they don't fit anymore
+    pub fn clear(&mut self) {
+        *self = History::new(self.limit);
+    }
+    /// Records the change from 'before' to 'after', which can then be undone.
+    /// Anything that was undone can't be redone anymore
+    pub fn record(&mut self, before: &[CellState], after: &[CellState]) {
+        if self.limit == 0 || before == after {
+            return;
+        }
+        self.push(Change::between(before, after, 0..before.len()), 0);
+    }
+    /// Records a generation going from 'before' to 'after', only the 'changed' cells
+    /// might differ. These are kept even if nothing changed, so undoing always goes
+    /// back a generation
+    pub fn record_step_changes(
+        &mut self,
+        before: &[CellState],
+        after: &[CellState],
+        changed: impl Iterator<Item = usize>,
+    ) {
+        if self.limit == 0 {
+            return;
+        }
+        self.push(Change::between(before, after, changed), 1);
+    }
+    /// Records an edit that's already known as a diff, eg. a few cells being set
+    pub fn record_diff(&mut self, diff: Diff) {
+        if self.limit == 0 || diff.is_empty() {
+            return;
+        }
+        self.push(Change::Cells(diff), 0);
+    }
+    fn push(&mut self, change: Change, steps: u64) {
+        self.redo.clear();
+        self.stored += change.stored_cells();
+        self.undo.push_back((change, steps));
+        self.trim();
+    }
+    /// Drops the oldest changes until the history fits its limits
+    fn trim(&mut self) {
+        while self.undo.len() > self.limit
+            || (self.stored > MAX_STORED_CELLS && self.undo.len() > 1)
+        {
+            let (change, _) = self.undo.pop_front().unwrap();
+            self.stored -= change.stored_cells();
+        }
+        if self.limit == 0 {
+            self.redo.clear();
+        }
+    }
+    /// Undoes the last change to the cells. Returns how many generations were undone
+    /// (0 for an edit), or None if there was nothing to undo
+    pub fn undo(&mut self, cells: &mut [CellState]) -> Option<u64> {
+        let (change, steps) = self.undo.pop_back()?;
+        change.undo(cells);
+        self.stored -= change.stored_cells();
+        self.redo.push((change, steps));
+        Some(steps)
+    }
+    /// Redoes the last undone change, like [History::undo]
+    pub fn redo(&mut self, cells: &mut [CellState]) -> Option<u64> {
+        let (change, steps) = self.redo.pop()?;
+        change.redo(cells);
+        self.stored += change.stored_cells();
+        self.undo.push_back((change, steps));
+        Some(steps)
+    }
+}
+impl Default for History {
+    fn default() -> Self {
+        History::new(DEFAULT_HISTORY_LIMIT)
+    }
+}
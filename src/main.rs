@@ -1,13 +1,35 @@
+mod active;
+mod apgcode;
+mod board;
+mod catalogue;
+mod cycle;
+mod diff;
 mod game;
 mod graphics;
+mod hashlife;
+mod history;
+mod neighborhood;
+mod packed;
+mod pattern;
+mod resize;
+mod rule;
 mod save_load;
+mod soup;
+mod sparse;
+mod stats;
 mod test;
 mod text;
+mod topology;
+mod verify;
 
 const GAME_X: usize = 120;
 const GAME_Y: usize = 80;
 const ALIVE_STATUS_CHARACTER: char = '✓'; // ☑
 const DEAD_STATUS_CHARACTER: char = '✗'; // ☒
+/// Cells that are dying (in rules w/ more than 2 states) are shown by how many
+/// generations they have left, so Dying(1) is '1', Dying(10) is 'a' and so on
+const DYING_STATUS_CHARACTERS: &str =
+    "123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
 
 #[allow(unused)]
 enum ProgramMode {
@@ -26,11 +48,86 @@ fn main() {
     })
     .expect("Failed to set Handler!");
 
-    if std::env::args().nth(1).is_some() {
-        graphics::incrementing_render();
-    }
+    // "--engine <name>" picks what runs the board, eg. "--engine packed" (see
+    // verify::ENGINES), it can go before or after the other arguments
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let engine = match args.iter().position(|a| a == "--engine") {
+        Some(i) => {
+            let name = args.get(i + 1).cloned().unwrap_or_default();
+            args.drain(i..(i + 2).min(args.len()));
+            let engine = verify::ENGINES.iter().find(|e| e.name == name);
+            *engine.unwrap_or_else(|| {
+                let names: Vec<&str> = verify::ENGINES.iter().map(|e| e.name).collect();
+                eprintln!("Unknown engine \"{name}\", pick one of: {}", names.join(", "));
+                std::process::exit(1);
+            })
+        }
+        None => verify::ENGINES[0],
+    };
+
+    // "--soup [settings]" starts w/ a random soup, eg. "--soup seed=1234 symmetry=C2"
+    let soup = match args.first().map(String::as_str) {
+        Some("--soup") => match args[1..].join(" ").parse::<soup::Soup>() {
+            Ok(soup) => Some(soup),
+            Err(e) => {
+                eprintln!("Couldn't read the soup: {e}");
+                std::process::exit(1);
+            }
+        },
+        // "--verify [number of soups]" checks the engines agree, then quits
+        Some("--verify") => {
+            let mut verify = verify::Verify::new();
+            if let Some(soups) = args.get(1) {
+                verify.soups = soups.parse().unwrap_or_else(|_| {
+                    eprintln!("Couldn't parse the number of soups");
+                    std::process::exit(1);
+                });
+            }
+            match verify.run(&verify::ENGINES) {
+                Ok(checked) => {
+                    println!("Every engine agreed on {checked} soups (seed {})", verify.seed);
+                    std::process::exit(0);
+                }
+                Err(divergence) => {
+                    eprintln!("{divergence}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        // "--convert <from> <to>" saves a pattern file in another format, then quits
+        Some("--convert") => {
+            let (Some(from), Some(to)) = (args.get(1), args.get(2)) else {
+                eprintln!("Pick the files to convert, eg. --convert glider.cells glider.rle");
+                std::process::exit(1);
+            };
+            match save_load::convert(from, to) {
+                Ok(_) => {
+                    println!("Converted {from} to {to}");
+                    std::process::exit(0);
+                }
+                Err(e) => {
+                    eprintln!("Couldn't convert the file: {e:?}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(_) => graphics::incrementing_render(),
+        None => None,
+    };
     match get_app_mode() {
-        ProgramMode::CommandLine => text::text(),
-        ProgramMode::Gui => graphics::gui(Some(save_load::load_game("board.txt").unwrap())),
+        ProgramMode::CommandLine => text::text(soup, engine.new),
+        ProgramMode::Gui => {
+            let board = match soup {
+                Some(soup) => {
+                    let mut board = (engine.new)(GAME_X, GAME_Y);
+                    board.apply_soup(&soup).unwrap_or_else(|e| eprintln!("{e}"));
+                    println!("Made the soup: {soup}");
+                    board
+                }
+                None => save_load::load_padded("board.txt", engine.new).unwrap(),
+            };
+            println!("Running the board w/ the \"{}\" engine", engine.name);
+            graphics::gui(Some(board), engine.new)
+        }
     }
 }
@@ -0,0 +1,54 @@
+/// Which cells around a cell count as its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Every cell in the square of the given radius, radius 1 is the normal 8 neighbors
+    Moore(u8),
+    /// Every cell within the given number of orthogonal steps, radius 1 is the 4
+    /// cells up, down, left & right
+    VonNeumann(u8),
+    /// The 6 neighbors of a hexagonal grid, skewed onto the square one: the Moore
+    /// neighborhood without the top right & bottom left corners
+    Hexagonal,
+}
+impl Neighborhood {
+    /// The (dx, dy) offsets of every neighbor, the cell itself isn't included
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let r = self.radius() as isize;
+        let mut offsets = Vec::new();
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let neighbor = match self {
+                    _ if (dx, dy) == (0, 0) => false,
+                    Neighborhood::Moore(_) => true,
+                    Neighborhood::VonNeumann(_) => dx.abs() + dy.abs() <= r,
+                    Neighborhood::Hexagonal => (dx, dy) != (1, -1) && (dx, dy) != (-1, 1),
+                };
+                if neighbor {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        offsets
+    }
+    /// How many neighbors each cell has
+    pub fn size(&self) -> usize {
+        let r = self.radius() as usize;
+        match self {
+            Neighborhood::Moore(_) => (2 * r + 1) * (2 * r + 1) - 1,
+            Neighborhood::VonNeumann(_) => 2 * r * (r + 1),
+            Neighborhood::Hexagonal => 6,
+        }
+    }
+    /// How far away the furthest neighbor is, in either direction
+    pub fn radius(&self) -> u8 {
+        match self {
+            Neighborhood::Moore(r) | Neighborhood::VonNeumann(r) => *r,
+            Neighborhood::Hexagonal => 1,
+        }
+    }
+}
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::Moore(1)
+    }
+}
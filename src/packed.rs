@@ -0,0 +1,311 @@
+use crate::board::Board;
+use crate::game::{CellState, Game};
+use crate::resize::Resize;
+use crate::rule::Rule;
+use crate::topology::Topology;
+
+const WORD_BITS: usize = 64;
+
+/// A board stored as bits, 64 cells to a u64. Each generation is worked out a
+/// whole word at a time by adding up the neighbors with bitwise adders, so this
+/// is much faster (and smaller) than [Game] on big boards.
+///
+/// Bit i of word w in a row is the cell at x = w * 64 + i. Any bits past the
+/// width of the board are always 0.
+///
+/// Only the bounded, torus and alive border topologies, and life-like rules
+/// are supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(unused)]
+pub struct PackedGame {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    cells: Vec<u64>,
+    rule: Rule,
+    topology: Topology,
+    /// How many generations the board has been stepped
+    generation: u64,
+}
+
+/// Adds up 3 bits in each position, returns the (sum, carry) bits
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (partial & c))
+}
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+#[allow(unused)]
+impl PackedGame {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        PackedGame {
+            width,
+            height,
+            words_per_row,
+            cells: vec![0; words_per_row * height],
+            rule: Rule::default(),
+            topology: Topology::default(),
+            generation: 0,
+        }
+    }
+    /// Copies a board, along with its rule & topology
+    pub fn from_game(game: &Game) -> Result<Self, &'static str> {
+        let mut packed = PackedGame::new(game.x_max, game.y_max);
+        packed.set_topology(game.topology())?;
+        packed.set_rule(game.rule())?;
+        for (y, row) in game.rows().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                packed.set(x, y, *cell);
+            }
+        }
+        Ok(packed)
+    }
+    pub fn to_game(&self) -> Game {
+        let mut game = Game::with_topology(self.width, self.height, self.topology);
+        game.set_rule(self.rule);
+        let mut cells = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            cells.extend((0..self.width).map(|x| self.get(x, y).unwrap()));
+        }
+        game.replace_buffer(cells).unwrap();
+        game
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// Changes the rule, only life-like rules are supported (see [Rule::is_life_like])
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        if !rule.is_life_like() {
+            return Err(
+                "packed boards only run totalistic 2 state rules on the 8 nearest neighbors",
+            );
+        }
+        self.rule = rule;
+        Ok(())
+    }
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+    pub fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        match topology {
+            Topology::Bounded | Topology::Torus | Topology::AliveBorder => {
+                self.topology = topology;
+                Ok(())
+            }
+            _ => Err("packed boards only support bounded, torus and alive border edges"),
+        }
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let word = self.cells[y * self.words_per_row + x / WORD_BITS];
+        match (word >> (x % WORD_BITS)) & 1 {
+            1 => Some(CellState::Alive),
+            _ => Some(CellState::Dead),
+        }
+    }
+    pub fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        assert!(x < self.width && y < self.height);
+        let word = &mut self.cells[y * self.words_per_row + x / WORD_BITS];
+        let bit = 1u64 << (x % WORD_BITS);
+        match cell {
+            CellState::Alive => *word |= bit,
+            CellState::Dead | CellState::Dying(_) => *word &= !bit,
+        }
+    }
+    /// Works the same as [Game::set_many]
+    ///
+    /// If cells.len() != 1 or pos.len(), the function will panic!
+    pub fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
+        if cells.len() != 1 {
+            assert_eq!(pos.len(), cells.len());
+            for ((x, y), c) in pos.iter().zip(cells) {
+                self.set(*x, *y, *c);
+            }
+            return;
+        }
+        for (x, y) in pos.iter() {
+            self.set(*x, *y, cells[0]);
+        }
+    }
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|w| *w = 0);
+    }
+    pub fn population(&self) -> usize {
+        self.cells.iter().map(|w| w.count_ones() as usize).sum()
+    }
+    /// Which bits of the last word in each row are part of the board
+    fn last_word_mask(&self) -> u64 {
+        match self.width % WORD_BITS {
+            0 => u64::MAX,
+            bits => (1u64 << bits) - 1,
+        }
+    }
+    /// Word 'i' of a row, where 'y' can be one past either edge of the board
+    fn word(&self, y: isize, i: usize) -> u64 {
+        let h = self.height as isize;
+        match self.topology {
+            _ if (0..h).contains(&y) => self.cells[y as usize * self.words_per_row + i],
+            Topology::Torus => self.word(y.rem_euclid(h), i),
+            Topology::AliveBorder if i + 1 == self.words_per_row => self.last_word_mask(),
+            Topology::AliveBorder => u64::MAX,
+            _ => 0,
+        }
+    }
+    /// Word 'i' of a row (see [PackedGame::word]) along w/ its shifted copies, where each
+    /// bit holds its west (x - 1) & east (x + 1) neighbor, as (west, word, east)
+    fn shifted(&self, y: isize, i: usize) -> (u64, u64, u64) {
+        let word = self.word(y, i);
+        let prev = if i > 0 { self.word(y, i - 1) } else { 0 };
+        let next = if i + 1 < self.words_per_row {
+            self.word(y, i + 1)
+        } else {
+            0
+        };
+        let mut west = (word << 1) | (prev >> (WORD_BITS - 1));
+        let mut east = (word >> 1) | (next << (WORD_BITS - 1));
+        // The cells at either end of the row are next to whatever is past the edge
+        let last = self.width - 1;
+        if i == 0 {
+            west |= match self.topology {
+                Topology::Torus => (self.word(y, last / WORD_BITS) >> (last % WORD_BITS)) & 1,
+                Topology::AliveBorder => 1,
+                _ => 0,
+            };
+        }
+        if i == last / WORD_BITS {
+            let past_east = match self.topology {
+                Topology::Torus => self.word(y, 0) & 1,
+                Topology::AliveBorder => 1,
+                _ => 0,
+            };
+            let bit = last % WORD_BITS;
+            east = (east & !(1u64 << bit)) | (past_east << bit);
+        }
+        (west, word, east)
+    }
+    /// Returns a mask of the positions where the 4 bit count equals n
+    fn count_is(bits: &[u64; 4], n: usize) -> u64 {
+        bits.iter().enumerate().fold(u64::MAX, |mask, (i, b)| {
+            if (n >> i) & 1 == 1 {
+                mask & b
+            } else {
+                mask & !b
+            }
+        })
+    }
+    fn iterate(&mut self) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+        let births: Vec<usize> = (0..=8)
+            .filter(|n| self.rule.next_state(CellState::Dead, *n) == CellState::Alive)
+            .collect();
+        let survivals: Vec<usize> = (0..=8)
+            .filter(|n| self.rule.next_state(CellState::Alive, *n) == CellState::Alive)
+            .collect();
+
+        let mask = self.last_word_mask();
+        let mut next = vec![0u64; self.cells.len()];
+        for y in 0..self.height {
+            for i in 0..self.words_per_row {
+                // Each word is shifted as it's needed, rows past the edges included
+                let row = y as isize;
+                let (above, here, below) = (
+                    self.shifted(row - 1, i),
+                    self.shifted(row, i),
+                    self.shifted(row + 1, i),
+                );
+                // Add up the 8 neighbors of every bit at once
+                let (s1, c1) = full_add(above.0, above.1, above.2);
+                let (s2, c2) = full_add(below.0, below.1, below.2);
+                let (s3, c3) = half_add(here.0, here.2);
+                let (bit0, c4) = full_add(s1, s2, s3);
+                let (t0, t1) = full_add(c1, c2, c3);
+                let (bit1, c5) = half_add(t0, c4);
+                let (bit2, bit3) = half_add(t1, c5);
+                let count = [bit0, bit1, bit2, bit3];
+
+                let alive = here.1;
+                let born = births
+                    .iter()
+                    .fold(0, |m, n| m | PackedGame::count_is(&count, *n));
+                let survives = survivals
+                    .iter()
+                    .fold(0, |m, n| m | PackedGame::count_is(&count, *n));
+                let mut word = (!alive & born) | (alive & survives);
+                if i + 1 == self.words_per_row {
+                    word &= mask;
+                }
+                next[y * self.words_per_row + i] = word;
+            }
+        }
+        self.cells = next;
+    }
+    pub fn step(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.iterate();
+        }
+        self.generation += steps as u64;
+    }
+}
+impl Board for PackedGame {
+    fn width(&self) -> usize {
+        self.width
+    }
+    fn height(&self) -> usize {
+        self.height
+    }
+    fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        PackedGame::get(self, x, y)
+    }
+    fn set(&mut self, x: usize, y: usize, cell: CellState) {
+        PackedGame::set(self, x, y, cell)
+    }
+    fn step(&mut self, steps: usize) {
+        PackedGame::step(self, steps)
+    }
+    fn rule(&self) -> Rule {
+        self.rule
+    }
+    fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        PackedGame::set_rule(self, rule)
+    }
+    fn topology(&self) -> Topology {
+        self.topology
+    }
+    fn set_topology(&mut self, topology: Topology) -> Result<(), &'static str> {
+        PackedGame::set_topology(self, topology)
+    }
+    fn resize(&mut self, resize: Resize) -> Result<(), &'static str> {
+        let mut game = self.to_game();
+        game.apply_resize(resize)?;
+        *self = PackedGame {
+            generation: self.generation,
+            ..PackedGame::from_game(&game)?
+        };
+        Ok(())
+    }
+    fn population(&self) -> usize {
+        PackedGame::population(self)
+    }
+    fn set_many(&mut self, pos: &[(usize, usize)], cells: &[CellState]) {
+        PackedGame::set_many(self, pos, cells)
+    }
+    fn clear(&mut self) {
+        PackedGame::clear(self)
+    }
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+}
@@ -0,0 +1,221 @@
+use std::fmt::Formatter;
+
+use crate::game::CellState;
+
+/// A rectangle of cells on a board, (x, y) is its top left corner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+impl Rect {
+    /// The rectangle between 2 opposite corners (both inclusive), in any order
+    pub fn from_corners(a: (usize, usize), b: (usize, usize)) -> Self {
+        Rect {
+            x: a.0.min(b.0),
+            y: a.1.min(b.1),
+            width: a.0.abs_diff(b.0) + 1,
+            height: a.1.abs_diff(b.1) + 1,
+        }
+    }
+    /// The part of the rectangle that's on a board of the given size, None if none of it is
+    pub fn clip(&self, width: usize, height: usize) -> Option<Rect> {
+        if self.x >= width || self.y >= height || self.width == 0 || self.height == 0 {
+            return None;
+        }
+        Some(Rect {
+            width: self.width.min(width - self.x),
+            height: self.height.min(height - self.y),
+            ..*self
+        })
+    }
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// A way to turn or mirror a pattern. Rotations are clockwise
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors the left & right sides
+    FlipHorizontal,
+    /// Mirrors the top & bottom
+    FlipVertical,
+}
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Transform::Rotate90 => "rot90",
+            Transform::Rotate180 => "rot180",
+            Transform::Rotate270 => "rot270",
+            Transform::FlipHorizontal => "flip-h",
+            Transform::FlipVertical => "flip-v",
+        };
+        write!(f, "{name}")
+    }
+}
+impl std::str::FromStr for Transform {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "rot90" | "90" => Ok(Transform::Rotate90),
+            "rot180" | "180" => Ok(Transform::Rotate180),
+            "rot270" | "270" => Ok(Transform::Rotate270),
+            "flip-h" | "h" => Ok(Transform::FlipHorizontal),
+            "flip-v" | "v" => Ok(Transform::FlipVertical),
+            _ => Err("unknown transform, expected rot90, rot180, rot270, flip-h or flip-v"),
+        }
+    }
+}
+
+/// How a pattern is combined w/ the cells it's pasted over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PasteMode {
+    /// Living cells are added, nothing is removed
+    #[default]
+    Or,
+    /// Living cells flip the cells under them
+    Xor,
+    /// Every cell is replaced, dead ones included
+    Overwrite,
+}
+impl PasteMode {
+    /// The next mode, eg. for a key that cycles through them
+    pub fn next(&self) -> PasteMode {
+        match self {
+            PasteMode::Or => PasteMode::Xor,
+            PasteMode::Xor => PasteMode::Overwrite,
+            PasteMode::Overwrite => PasteMode::Or,
+        }
+    }
+}
+impl std::fmt::Display for PasteMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PasteMode::Or => "or",
+            PasteMode::Xor => "xor",
+            PasteMode::Overwrite => "overwrite",
+        };
+        write!(f, "{name}")
+    }
+}
+impl std::str::FromStr for PasteMode {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "or" | "o" => Ok(PasteMode::Or),
+            "xor" | "x" => Ok(PasteMode::Xor),
+            "overwrite" | "w" => Ok(PasteMode::Overwrite),
+            _ => Err("unknown paste mode, expected or, xor or overwrite"),
+        }
+    }
+}
+
+/// A rectangle of cells that can be copied off a board & pasted back onto one,
+/// stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    width: usize,
+    height: usize,
+    cells: Vec<CellState>,
+}
+impl Pattern {
+    /// Makes a pattern from its cells, row by row. Fails if there aren't width * height
+    #[allow(unused)]
+    pub fn new(width: usize, height: usize, cells: Vec<CellState>) -> Result<Self, &'static str> {
+        if cells.len() != width * height {
+            return Err("Can't make a pattern: the cells don't fill the rectangle");
+        }
+        Ok(Pattern {
+            width,
+            height,
+            cells,
+        })
+    }
+    /// A pattern w/ nothing alive
+    pub fn dead(width: usize, height: usize) -> Self {
+        Pattern {
+            width,
+            height,
+            cells: vec![CellState::Dead; width * height],
+        }
+    }
+    /// Copies a rectangle of a board stored row by row. The rectangle has to be on the board
+    pub fn copy_from(cells: &[CellState], board_width: usize, area: Rect) -> Self {
+        let cells = (area.y..area.y + area.height)
+            .flat_map(|y| {
+                let start = y * board_width + area.x;
+                cells[start..start + area.width].iter().copied()
+            })
+            .collect();
+        Pattern {
+            width: area.width,
+            height: area.height,
+            cells,
+        }
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn get(&self, x: usize, y: usize) -> CellState {
+        self.cells[y * self.width + x]
+    }
+    /// The pattern turned or mirrored
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let (w, h) = (self.width, self.height);
+        let (width, height) = match transform {
+            Transform::Rotate90 | Transform::Rotate270 => (h, w),
+            _ => (w, h),
+        };
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for y in 0..height {
+            for x in 0..width {
+                cells.push(match transform {
+                    Transform::Rotate90 => self.get(y, h - 1 - x),
+                    Transform::Rotate180 => self.get(w - 1 - x, h - 1 - y),
+                    Transform::Rotate270 => self.get(w - 1 - y, x),
+                    Transform::FlipHorizontal => self.get(w - 1 - x, y),
+                    Transform::FlipVertical => self.get(x, h - 1 - y),
+                });
+            }
+        }
+        Pattern {
+            width,
+            height,
+            cells,
+        }
+    }
+    /// Pastes the pattern w/ its top left corner at 'at' onto a board stored row by
+    /// row. Anything that doesn't fit on the board is left off
+    pub fn paste_onto(
+        &self,
+        cells: &mut [CellState],
+        board_width: usize,
+        at: (usize, usize),
+        mode: PasteMode,
+    ) {
+        let board_height = cells.len() / board_width.max(1);
+        for y in 0..self.height.min(board_height.saturating_sub(at.1)) {
+            for x in 0..self.width.min(board_width.saturating_sub(at.0)) {
+                let cell = &mut cells[(at.1 + y) * board_width + at.0 + x];
+                *cell = match (mode, self.get(x, y)) {
+                    (PasteMode::Overwrite, new) => new,
+                    (PasteMode::Or, CellState::Alive) => CellState::Alive,
+                    (PasteMode::Xor, CellState::Alive) if *cell == CellState::Alive => {
+                        CellState::Dead
+                    }
+                    (PasteMode::Xor, CellState::Alive) => CellState::Alive,
+                    _ => *cell,
+                };
+            }
+        }
+    }
+}
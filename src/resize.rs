@@ -0,0 +1,141 @@
+use std::fmt::Formatter;
+
+use crate::game::CellState;
+use crate::stats::Stats;
+
+/// Which part of a board stays in place when it's resized, eg. TopLeft keeps the
+/// top left corner where it is and grows (or shrinks) the board right & down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+impl Anchor {
+    /// Where the top left corner of a (width, height) board 'from' ends up on a board
+    /// of size 'to'. Can be negative when the board shrinks
+    pub fn offset(&self, from: (usize, usize), to: (usize, usize)) -> (isize, isize) {
+        // How far along each side the anchor is, in halves
+        let (x, y) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        };
+        let grow = |from: usize, to: usize| to as isize - from as isize;
+        (grow(from.0, to.0) * x / 2, grow(from.1, to.1) * y / 2)
+    }
+}
+impl std::fmt::Display for Anchor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Anchor::TopLeft => "top-left",
+            Anchor::Top => "top",
+            Anchor::TopRight => "top-right",
+            Anchor::Left => "left",
+            Anchor::Center => "center",
+            Anchor::Right => "right",
+            Anchor::BottomLeft => "bottom-left",
+            Anchor::Bottom => "bottom",
+            Anchor::BottomRight => "bottom-right",
+        };
+        write!(f, "{name}")
+    }
+}
+impl std::str::FromStr for Anchor {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(' ', "-").as_str() {
+            "top-left" | "tl" => Ok(Anchor::TopLeft),
+            "top" | "t" => Ok(Anchor::Top),
+            "top-right" | "tr" => Ok(Anchor::TopRight),
+            "left" | "l" => Ok(Anchor::Left),
+            "center" | "centre" | "c" => Ok(Anchor::Center),
+            "right" | "r" => Ok(Anchor::Right),
+            "bottom-left" | "bl" => Ok(Anchor::BottomLeft),
+            "bottom" | "b" => Ok(Anchor::Bottom),
+            "bottom-right" | "br" => Ok(Anchor::BottomRight),
+            _ => Err("unknown anchor, expected tl, t, tr, l, c, r, bl, b or br"),
+        }
+    }
+}
+
+/// A change to the size of a board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resize {
+    /// A new (width, height), keeping the anchored part of the board in place
+    To(usize, usize, Anchor),
+    /// Adds this many dead cells to every side
+    Pad(usize),
+    /// Shrinks the board down to the smallest rectangle holding every living cell
+    CropToLive,
+}
+impl Resize {
+    /// Grows a board of size 'from' (centred) to at least the size 'to', eg. so a
+    /// small pattern has room to grow in. Dimensions that are already big enough are kept
+    pub fn at_least(from: (usize, usize), to: (usize, usize)) -> Resize {
+        Resize::To(from.0.max(to.0), from.1.max(to.1), Anchor::Center)
+    }
+    /// Resizes a board stored row by row, returns the new cells & (width, height)
+    pub fn apply(
+        &self,
+        cells: &[CellState],
+        size: (usize, usize),
+    ) -> Result<(Vec<CellState>, (usize, usize)), &'static str> {
+        if size.0 == 0 || size.1 == 0 {
+            return Err("Can't resize an empty board");
+        }
+        let (new_size, offset) = match *self {
+            Resize::To(width, height, anchor) => {
+                ((width, height), anchor.offset(size, (width, height)))
+            }
+            Resize::Pad(n) => ((size.0 + 2 * n, size.1 + 2 * n), (n as isize, n as isize)),
+            Resize::CropToLive => match Stats::measure(cells, size.0, 0).bounding_box {
+                Some(((x0, y0), (x1, y1))) => {
+                    ((x1 - x0 + 1, y1 - y0 + 1), (-(x0 as isize), -(y0 as isize)))
+                }
+                None => return Err("Can't crop the board, nothing is alive"),
+            },
+        };
+        if new_size.0 == 0 || new_size.1 == 0 {
+            return Err("Can't resize the board to nothing");
+        }
+        Ok((move_cells(cells, size, new_size, offset), new_size))
+    }
+}
+
+/// Copies the cells of a board of size 'from' onto a dead board of size 'to', w/ each
+/// cell moved by 'offset'. Cells that end up off the new board are dropped
+fn move_cells(
+    cells: &[CellState],
+    from: (usize, usize),
+    to: (usize, usize),
+    offset: (isize, isize),
+) -> Vec<CellState> {
+    let mut moved = vec![CellState::Dead; to.0 * to.1];
+    for (y, row) in cells.chunks_exact(from.0).enumerate() {
+        let new_y = y as isize + offset.1;
+        if !(0..to.1 as isize).contains(&new_y) {
+            continue;
+        }
+        for (x, cell) in row.iter().enumerate() {
+            let new_x = x as isize + offset.0;
+            if (0..to.0 as isize).contains(&new_x) {
+                moved[new_y as usize * to.0 + new_x as usize] = *cell;
+            }
+        }
+    }
+    moved
+}
@@ -0,0 +1,661 @@
+use std::fmt::Formatter;
+
+use crate::game::CellState;
+use crate::neighborhood::Neighborhood;
+use crate::DYING_STATUS_CHARACTERS;
+
+/// The most states a rule can have, limited by how many dying cells can be saved
+pub const MAX_STATES: u32 = DYING_STATUS_CHARACTERS.len() as u32 + 2;
+/// The biggest Larger than Life radius, so every count fits in [Counts]
+pub const MAX_RADIUS: u32 = 7;
+
+/// Everything that can go wrong while reading a rulestring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleError {
+    Empty,
+    /// The rulestring didn't have 2 or 3 sections separated by '/'
+    WrongSectionCount(usize),
+    /// A section had a letter other than 'B' or 'S' in front of it
+    UnknownSection(char),
+    /// Both halves of the rulestring were for the same thing (eg. "B3/B23")
+    RepeatedSection(char),
+    /// A neighbor count that isn't a digit
+    InvalidCharacter(char),
+    /// A neighbor count that is bigger than the neighborhood (eg. "B9")
+    CountOutOfRange(u32),
+    /// The number of states wasn't a number
+    InvalidStateCount,
+    /// Rules need at least 2 states (dead & alive), and at most [MAX_STATES]
+    StateCountOutOfRange(u32),
+    /// A Larger than Life rule without an "R" section
+    MissingRadius,
+    /// Larger than Life radii go from 1 up to [MAX_RADIUS]
+    RadiusOutOfRange(u32),
+    /// A Larger than Life range that isn't "a..b" (w/ a <= b) or a single number
+    InvalidRange,
+    /// A Larger than Life neighborhood other than NM (Moore) or NN (von Neumann)
+    UnknownNeighborhood(char),
+    /// A letter that isn't one of the Hensel letters for that many neighbors (eg. "B1a")
+    InvalidLetter(u32, char),
+}
+impl std::fmt::Display for RuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleError::Empty => write!(f, "the rulestring is empty"),
+            RuleError::WrongSectionCount(n) => write!(
+                f,
+                "expected 2 or 3 sections separated by '/' (like B3/S23 or B2/S/C3), found {n}"
+            ),
+            RuleError::UnknownSection(c) => {
+                write!(f, "unknown section '{c}', sections start with 'B' or 'S'")
+            }
+            RuleError::RepeatedSection(c) => write!(f, "the '{c}' section is given twice"),
+            RuleError::InvalidCharacter(c) => {
+                write!(f, "'{c}' is not a neighbor count, expected a digit 0-8")
+            }
+            RuleError::CountOutOfRange(n) => {
+                write!(f, "a cell can't have {n} neighbors in this neighborhood")
+            }
+            RuleError::InvalidStateCount => {
+                write!(f, "the number of states should be a number, like C3")
+            }
+            RuleError::StateCountOutOfRange(n) => write!(
+                f,
+                "a rule can't have {n} states, expected between 2 and {MAX_STATES}"
+            ),
+            RuleError::MissingRadius => write!(
+                f,
+                "Larger than Life rules need a radius, like R5,C0,M1,S34..58,B34..45,NM"
+            ),
+            RuleError::RadiusOutOfRange(r) => {
+                write!(
+                    f,
+                    "a radius of {r} isn't supported, expected 1 to {MAX_RADIUS}"
+                )
+            }
+            RuleError::InvalidRange => {
+                write!(
+                    f,
+                    "neighbor counts should be a range like 34..58, or one number"
+                )
+            }
+            RuleError::UnknownNeighborhood(c) => write!(
+                f,
+                "unknown neighborhood 'N{c}', expected NM (Moore) or NN (von Neumann)"
+            ),
+            RuleError::InvalidLetter(n, c) => {
+                write!(f, "'{c}' isn't a Hensel letter for {n} neighbors")
+            }
+        }
+    }
+}
+
+/// A set of neighbor counts, from 0 up to 255
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+struct Counts([u64; 4]);
+impl Counts {
+    fn insert(&mut self, n: usize) {
+        self.0[n / 64] |= 1 << (n % 64);
+    }
+    fn contains(&self, n: usize) -> bool {
+        n < 256 && self.0[n / 64] & (1 << (n % 64)) != 0
+    }
+    fn remove(&mut self, n: usize) {
+        self.0[n / 64] &= !(1 << (n % 64));
+    }
+    fn extend(&mut self, other: &Counts) {
+        other.iter().for_each(|n| self.insert(n));
+    }
+    fn is_subset(&self, other: &Counts) -> bool {
+        self.iter().all(|n| other.contains(n))
+    }
+    fn is_disjoint(&self, other: &Counts) -> bool {
+        self.iter().all(|n| !other.contains(n))
+    }
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..256).filter(|n| self.contains(*n))
+    }
+    /// The counts grouped into runs of consecutive numbers, as (first, last)
+    fn runs(&self) -> Vec<(usize, usize)> {
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for n in self.iter() {
+            match runs.last_mut() {
+                Some((_, last)) if *last + 1 == n => *last = n,
+                _ => runs.push((n, n)),
+            }
+        }
+        runs
+    }
+}
+
+/// A "life-like" rule: whether a cell is born or survives depends only on how many
+/// of its 8 neighbors are alive. Conway's Game of Life is B3/S23.
+///
+/// Rules can be parsed from the usual notation, eg. "B36/S23" (HighLife), or
+/// the older survival/birth form without letters, eg. "23/36".
+///
+/// "Generations" rules have more than 2 states: living cells that don't survive
+/// spend (states - 2) generations dying before they are dead, and dying cells can't
+/// be born or count as neighbors. These are written w/ a third section, eg.
+/// "B2/S/C3" or "/2/3" (Brian's Brain) and "345/2/4" (Star Wars).
+///
+/// Isotropic non-totalistic rules also look at how the 8 neighbors are arranged, w/
+/// Hensel's letters after each count, eg. "B2-a/S12" or "B3/S2-i34q". Each letter
+/// is one arrangement of the neighbors along w/ its rotations & reflections.
+///
+/// Other neighborhoods are written w/ a letter on the end, "B2/S34H" (hexagonal) or
+/// "B13/S012V" (von Neumann), or in the Larger than Life notation for bigger ones,
+/// eg. "R5,C0,M1,S34..58,B34..45,NM" (Bosco's rule).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    birth: Counts,
+    survival: Counts,
+    states: u8,
+    neighborhood: Neighborhood,
+    /// Whether the cell itself is counted as one of its neighbors (Larger than Life's "M1")
+    include_center: bool,
+    /// Whether birth & survival hold neighbor masks (see [Rule::next_state_isotropic])
+    /// instead of neighbor counts
+    isotropic: bool,
+}
+impl Rule {
+    /// The standard Game of Life rule, B3/S23
+    pub fn conway() -> Self {
+        let mut rule = Rule {
+            birth: Counts::default(),
+            survival: Counts::default(),
+            states: 2,
+            neighborhood: Neighborhood::Moore(1),
+            include_center: false,
+            isotropic: false,
+        };
+        rule.birth.insert(3);
+        rule.survival.insert(2);
+        rule.survival.insert(3);
+        rule
+    }
+    pub fn parse(rulestring: &str) -> Result<Self, RuleError> {
+        let rulestring = rulestring.trim();
+        if rulestring.is_empty() {
+            return Err(RuleError::Empty);
+        }
+        if rulestring.starts_with(['R', 'r']) && rulestring.contains(',') {
+            return parse_larger_than_life(rulestring);
+        }
+
+        let (rulestring, neighborhood) = match rulestring.char_indices().last() {
+            Some((i, 'V' | 'v')) => (&rulestring[..i], Neighborhood::VonNeumann(1)),
+            Some((i, 'H' | 'h')) => (&rulestring[..i], Neighborhood::Hexagonal),
+            _ => (rulestring, Neighborhood::Moore(1)),
+        };
+        let max = neighborhood.size();
+        let mut sections: Vec<&str> = rulestring.split('/').collect();
+        let states = match sections.len() {
+            2 => 2,
+            3 => parse_states(sections.pop().unwrap())?,
+            n => return Err(RuleError::WrongSectionCount(n)),
+        };
+        // Any letters after the counts are Hensel letters
+        let isotropic = neighborhood == Neighborhood::Moore(1)
+            && sections
+                .iter()
+                .any(|s| s.chars().skip(1).any(|c| !c.is_ascii_digit()));
+        let counts = |section: &str| match isotropic {
+            true => parse_hensel(section),
+            false => parse_counts(section, max),
+        };
+        let rule = |birth, survival| {
+            Rule {
+                birth,
+                survival,
+                states,
+                neighborhood,
+                include_center: false,
+                isotropic,
+            }
+            .simplify()
+        };
+
+        // Without letters the (older) order is survival/birth
+        let has_letters = sections
+            .iter()
+            .any(|s| s.starts_with(|c: char| c.is_ascii_alphabetic()));
+        if !has_letters {
+            return Ok(rule(counts(sections[1])?, counts(sections[0])?));
+        }
+
+        let (mut birth, mut survival) = (None, None);
+        for section in sections {
+            let mut chars = section.chars();
+            let (slot, letter) = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => (&mut birth, 'B'),
+                Some('S') => (&mut survival, 'S'),
+                Some(c) => return Err(RuleError::UnknownSection(c)),
+                None => return Err(RuleError::UnknownSection('/')),
+            };
+            if slot.is_some() {
+                return Err(RuleError::RepeatedSection(letter));
+            }
+            *slot = Some(counts(chars.as_str())?);
+        }
+        Ok(rule(
+            birth.ok_or(RuleError::RepeatedSection('S'))?,
+            survival.ok_or(RuleError::RepeatedSection('B'))?,
+        ))
+    }
+    /// How many states a cell can be in, 2 for normal (dead & alive) rules
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+    pub fn neighborhood(&self) -> Neighborhood {
+        self.neighborhood
+    }
+    /// The (dx, dy) offsets of the cells that are counted for each cell
+    /// (including (0, 0) if the cell counts itself)
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let mut offsets = self.neighborhood.offsets();
+        if self.include_center {
+            offsets.push((0, 0));
+        }
+        offsets
+    }
+    /// Whether this is a plain 2 state totalistic rule on the 8 nearest neighbors,
+    /// which is all the HashLife, packed and sparse boards can run
+    pub fn is_life_like(&self) -> bool {
+        self.states == 2
+            && self.neighborhood == Neighborhood::Moore(1)
+            && !self.include_center
+            && !self.isotropic
+    }
+    /// The older survival/birth form of the rule, eg. "23/3" for B3/S23. Only
+    /// life-like rules (see [Rule::is_life_like]) can be written this way
+    pub fn survival_birth(&self) -> Option<String> {
+        if !self.is_life_like() {
+            return None;
+        }
+        let digits = |counts: &Counts| counts.iter().map(|n| n.to_string()).collect::<String>();
+        let (survival, birth) = (digits(&self.survival), digits(&self.birth));
+        Some(format!("{survival}/{birth}"))
+    }
+    /// Whether the arrangement of the neighbors matters, not just how many are alive.
+    /// If so, use [Rule::next_state_isotropic] instead of [Rule::next_state]
+    pub fn is_isotropic(&self) -> bool {
+        self.isotropic
+    }
+    /// Returns what the given cell becomes, given how many of its neighbors are alive
+    pub fn next_state(&self, cell: CellState, alive_neighbors: usize) -> CellState {
+        self.next(cell, alive_neighbors)
+    }
+    /// Returns what the given cell becomes for an isotropic rule. Bit i of the mask is
+    /// set if the i-th of the 8 neighbors is alive, in the order of [Rule::offsets]
+    /// (see [crate::topology::Topology::neighbor_mask])
+    pub fn next_state_isotropic(&self, cell: CellState, mask: u64) -> CellState {
+        self.next(cell, mask as usize)
+    }
+    /// Looks the cell up in the birth or survival table, which are either counts or masks
+    fn next(&self, cell: CellState, neighbors: usize) -> CellState {
+        let table = match cell {
+            CellState::Alive => &self.survival,
+            CellState::Dead => &self.birth,
+            CellState::Dying(1) => return CellState::Dead,
+            CellState::Dying(left) => return CellState::Dying(left - 1),
+        };
+        match (table.contains(neighbors), cell) {
+            (true, _) => CellState::Alive,
+            (_, CellState::Alive) if self.states > 2 => CellState::Dying(self.states - 2),
+            _ => CellState::Dead,
+        }
+    }
+    /// Turns an isotropic rule that only uses whole counts back into a totalistic one
+    fn simplify(mut self) -> Self {
+        if !self.isotropic {
+            return self;
+        }
+        let whole = |masks: &Counts| {
+            (0..=8).all(|n| {
+                let all = masks_with(n);
+                all.is_subset(masks) || all.is_disjoint(masks)
+            })
+        };
+        if !whole(&self.birth) || !whole(&self.survival) {
+            return self;
+        }
+        let counts = |masks: &Counts| {
+            let mut counts = Counts::default();
+            (0..=8)
+                .filter(|n| masks.contains(masks_with(*n).iter().next().unwrap()))
+                .for_each(|n| counts.insert(n));
+            counts
+        };
+        self.birth = counts(&self.birth);
+        self.survival = counts(&self.survival);
+        self.isotropic = false;
+        self
+    }
+}
+
+// The 8 neighbors, as offsets
+const N: (isize, isize) = (0, -1);
+const NE: (isize, isize) = (1, -1);
+const E: (isize, isize) = (1, 0);
+const SE: (isize, isize) = (1, 1);
+const S: (isize, isize) = (0, 1);
+const SW: (isize, isize) = (-1, 1);
+const W: (isize, isize) = (-1, 0);
+const NW: (isize, isize) = (-1, -1);
+/// The Hensel letters for 0 to 4 neighbors, 5 to 8 use the letters of 8 - n
+const HENSEL_LETTERS: [&str; 5] = ["", "ce", "aceikn", "aceijknqry", "aceijknqrtwyz"];
+/// One arrangement of the living neighbors for each of the letters above, the rest are
+/// its rotations & reflections. For 5 to 8 neighbors these are the dead ones
+const HENSEL_NEIGHBORS: [&[&[(isize, isize)]]; 5] = [
+    &[],
+    &[&[NE], &[N]],
+    &[&[N, NE], &[NE, SE], &[N, E], &[N, S], &[N, SE], &[NE, SW]],
+    &[
+        &[N, NE, E],
+        &[NE, SE, SW],
+        &[N, E, S],
+        &[N, NE, NW],
+        &[N, NE, W],
+        &[N, E, SW],
+        &[N, NE, SE],
+        &[N, NE, SW],
+        &[N, NE, S],
+        &[N, SE, SW],
+    ],
+    &[
+        &[N, NE, E, SE],
+        &[NE, SE, SW, NW],
+        &[N, E, S, W],
+        &[N, NE, SE, S],
+        &[N, NE, S, W],
+        &[N, NE, SE, W],
+        &[N, NE, SE, NW],
+        &[N, NE, E, SW],
+        &[N, NE, E, S],
+        &[N, NE, S, NW],
+        &[N, NE, SW, W],
+        &[N, NE, SE, SW],
+        &[N, NE, S, SW],
+    ],
+];
+/// The bit of a neighbor in a neighbor mask, the Moore neighbors are in reading order
+fn mask_bit((dx, dy): (isize, isize)) -> usize {
+    let i = ((dy + 1) * 3 + dx + 1) as usize;
+    if i > 4 {
+        i - 1
+    } else {
+        i
+    }
+}
+/// Every neighbor mask w/ n living neighbors
+fn masks_with(n: usize) -> Counts {
+    let mut masks = Counts::default();
+    (0..256usize)
+        .filter(|m| m.count_ones() as usize == n)
+        .for_each(|m| masks.insert(m));
+    masks
+}
+/// Every neighbor mask in the Hensel class of the letter, for n living neighbors
+fn hensel_class(n: usize, letter: char) -> Option<Counts> {
+    let small = n.min(8 - n);
+    let index = HENSEL_LETTERS[small].find(letter)?;
+    let mut class = Counts::default();
+    for rotations in 0..4 {
+        for flip in [false, true] {
+            let mut mask = 0;
+            for &(mut dx, mut dy) in HENSEL_NEIGHBORS[small][index] {
+                if flip {
+                    dx = -dx;
+                }
+                for _ in 0..rotations {
+                    (dx, dy) = (-dy, dx);
+                }
+                mask |= 1 << mask_bit((dx, dy));
+            }
+            if n > 4 {
+                mask ^= 0xff;
+            }
+            class.insert(mask);
+        }
+    }
+    Some(class)
+}
+/// Reads isotropic non-totalistic counts like "2-a34q" into the set of neighbor masks.
+/// A count w/o letters is every arrangement, a '-' takes the letters after it away
+fn parse_hensel(section: &str) -> Result<Counts, RuleError> {
+    let mut masks = Counts::default();
+    let mut chars = section.chars().peekable();
+    while let Some(c) = chars.next() {
+        let n = c.to_digit(10).ok_or(RuleError::InvalidCharacter(c))?;
+        if n > 8 {
+            return Err(RuleError::CountOutOfRange(n));
+        }
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = Counts::default();
+        let mut any = false;
+        while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+            let class =
+                hensel_class(n as usize, letter).ok_or(RuleError::InvalidLetter(n, letter))?;
+            letters.extend(&class);
+            any = true;
+        }
+        match (any, negated) {
+            (false, true) => return Err(RuleError::InvalidCharacter('-')),
+            (false, false) => masks.extend(&masks_with(n as usize)),
+            (true, false) => masks.extend(&letters),
+            (true, true) => {
+                let mut all = masks_with(n as usize);
+                letters.iter().for_each(|m| all.remove(m));
+                masks.extend(&all);
+            }
+        }
+    }
+    Ok(masks)
+}
+/// Writes a set of neighbor masks in Hensel's notation, using a '-' when that's shorter
+fn write_hensel(f: &mut Formatter<'_>, masks: &Counts) -> std::fmt::Result {
+    for n in 0..=8 {
+        let all = masks_with(n);
+        if all.is_subset(masks) {
+            write!(f, "{n}")?;
+            continue;
+        }
+        let (with, without): (String, String) = HENSEL_LETTERS[n.min(8 - n)]
+            .chars()
+            .partition(|l| hensel_class(n, *l).unwrap().is_subset(masks));
+        if with.is_empty() {
+            continue;
+        }
+        match with.len() <= without.len() {
+            true => write!(f, "{n}{with}")?,
+            false => write!(f, "{n}-{without}")?,
+        }
+    }
+    Ok(())
+}
+
+fn parse_states(section: &str) -> Result<u8, RuleError> {
+    let digits = section.trim_start_matches(['C', 'c', 'G', 'g']);
+    let states: u32 = digits.parse().map_err(|_| RuleError::InvalidStateCount)?;
+    if !(2..=MAX_STATES).contains(&states) {
+        return Err(RuleError::StateCountOutOfRange(states));
+    }
+    Ok(states as u8)
+}
+fn parse_counts(section: &str, max: usize) -> Result<Counts, RuleError> {
+    let mut counts = Counts::default();
+    for c in section.chars() {
+        let n = c.to_digit(10).ok_or(RuleError::InvalidCharacter(c))?;
+        if n as usize > max {
+            return Err(RuleError::CountOutOfRange(n));
+        }
+        counts.insert(n as usize);
+    }
+    Ok(counts)
+}
+/// Reads a rule written like "R5,C0,M1,S34..58,B34..45,NM"
+fn parse_larger_than_life(rulestring: &str) -> Result<Rule, RuleError> {
+    let mut radius = None;
+    let mut rule = Rule::conway();
+    rule.birth = Counts::default();
+    rule.survival = Counts::default();
+    let mut von_neumann = false;
+    // Extra ranges (eg. the "5..6" in "S2..3,5..6") belong to the section before them
+    let mut last = None;
+
+    for token in rulestring.split(',').map(str::trim) {
+        let mut chars = token.chars();
+        let first = chars.next().ok_or(RuleError::InvalidRange)?;
+        let rest = chars.as_str();
+        match first.to_ascii_uppercase() {
+            'R' => radius = Some(rest.parse::<u32>().map_err(|_| RuleError::MissingRadius)?),
+            'C' => {
+                rule.states = match rest {
+                    "0" | "1" => 2,
+                    _ => parse_states(rest)?,
+                }
+            }
+            'M' => {
+                rule.include_center = match rest {
+                    "0" => false,
+                    "1" => true,
+                    _ => {
+                        return Err(RuleError::InvalidCharacter(
+                            rest.chars().next().unwrap_or(first),
+                        ))
+                    }
+                }
+            }
+            'S' => {
+                parse_range(rest, &mut rule.survival)?;
+                last = Some('S');
+            }
+            'B' => {
+                parse_range(rest, &mut rule.birth)?;
+                last = Some('B');
+            }
+            'N' => match rest.to_ascii_uppercase().as_str() {
+                "M" => von_neumann = false,
+                "N" => von_neumann = true,
+                _ => {
+                    return Err(RuleError::UnknownNeighborhood(
+                        rest.chars().next().unwrap_or(' '),
+                    ))
+                }
+            },
+            c if c.is_ascii_digit() => match last {
+                Some('S') => parse_range(token, &mut rule.survival)?,
+                Some('B') => parse_range(token, &mut rule.birth)?,
+                _ => return Err(RuleError::InvalidRange),
+            },
+            c => return Err(RuleError::UnknownSection(c)),
+        }
+    }
+
+    let radius = radius.ok_or(RuleError::MissingRadius)?;
+    if !(1..=MAX_RADIUS).contains(&radius) {
+        return Err(RuleError::RadiusOutOfRange(radius));
+    }
+    rule.neighborhood = match von_neumann {
+        true => Neighborhood::VonNeumann(radius as u8),
+        false => Neighborhood::Moore(radius as u8),
+    };
+    let max = rule.offsets().len();
+    if let Some(n) = rule
+        .birth
+        .iter()
+        .chain(rule.survival.iter())
+        .find(|n| *n > max)
+    {
+        return Err(RuleError::CountOutOfRange(n as u32));
+    }
+    Ok(rule)
+}
+/// Reads "a..b" or "a" into the set of counts, an empty range adds nothing
+fn parse_range(range: &str, counts: &mut Counts) -> Result<(), RuleError> {
+    if range.is_empty() {
+        return Ok(());
+    }
+    let (low, high) = range.split_once("..").unwrap_or((range, range));
+    let low: usize = low.parse().map_err(|_| RuleError::InvalidRange)?;
+    let high: usize = high.parse().map_err(|_| RuleError::InvalidRange)?;
+    if low > high {
+        return Err(RuleError::InvalidRange);
+    }
+    if high > 255 {
+        return Err(RuleError::CountOutOfRange(high as u32));
+    }
+    (low..=high).for_each(|n| counts.insert(n));
+    Ok(())
+}
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+impl std::str::FromStr for Rule {
+    type Err = RuleError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::parse(s)
+    }
+}
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let suffix = match self.neighborhood {
+            _ if self.include_center => None,
+            Neighborhood::Moore(1) => Some(""),
+            Neighborhood::VonNeumann(1) => Some("V"),
+            Neighborhood::Hexagonal => Some("H"),
+            _ => None,
+        };
+        let Some(suffix) = suffix else {
+            // Only the Larger than Life notation can write this rule
+            let ranges = |counts: &Counts| {
+                let runs: Vec<String> = counts
+                    .runs()
+                    .iter()
+                    .map(|(first, last)| format!("{first}..{last}"))
+                    .collect();
+                runs.join(",")
+            };
+            return write!(
+                f,
+                "R{},C{},M{},S{},B{},N{}",
+                self.neighborhood.radius(),
+                if self.states > 2 { self.states } else { 0 },
+                self.include_center as u8,
+                ranges(&self.survival),
+                ranges(&self.birth),
+                match self.neighborhood {
+                    Neighborhood::VonNeumann(_) => 'N',
+                    _ => 'M',
+                }
+            );
+        };
+
+        write!(f, "B")?;
+        if self.isotropic {
+            write_hensel(f, &self.birth)?;
+            write!(f, "/S")?;
+            write_hensel(f, &self.survival)?;
+        } else {
+            for n in self.birth.iter() {
+                write!(f, "{n}")?;
+            }
+            write!(f, "/S")?;
+            for n in self.survival.iter() {
+                write!(f, "{n}")?;
+            }
+        }
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        write!(f, "{suffix}")
+    }
+}
+impl std::fmt::Debug for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -1,10 +1,113 @@
+use crate::board::Board;
 use crate::game::CellState;
-use crate::{game, text, ALIVE_STATUS_CHARACTER, DEAD_STATUS_CHARACTER, GAME_X, GAME_Y};
+use crate::hashlife::HashLife;
+use crate::pattern::{Pattern, Rect};
+use crate::resize::{Anchor, Resize};
+use crate::rule::Rule;
+use crate::topology::Topology;
+use crate::{game, text, GAME_X, GAME_Y};
 use core::str;
 
+/// Save files can start with header lines like "#topology torus" or "#rule B3/S23"
+const HEADER_START: char = '#';
+const TOPOLOGY_HEADER: &str = "topology";
+const RULE_HEADER: &str = "rule";
+/// RLE files wrap their lines at this many characters
+const RLE_LINE_LENGTH: usize = 70;
+/// The first line of a Life 1.05 or 1.06 file, followed by the version
+const LIFE_HEADER: &str = "#Life";
+/// The first line of a macrocell file, followed by what wrote it
+const MACROCELL_HEADER: &str = "[M2]";
+/// Macrocell patterns (& the sizes other formats claim) can be far too big for a
+/// [game::Game], they're only loaded onto one if they're no wider or taller than this
+pub(crate) const MACROCELL_FLATTEN_LIMIT: usize = 4096;
+
+/// The kinds of file a board can be saved as, picked by the file's extension
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Format {
+    /// A grid of ✓ & ✗, w/ "#rule" & "#topology" header lines
+    Native,
+    /// Run length encoded (.rle), the usual way patterns are shared
+    Rle,
+    /// Plain text (.cells), rows of '.' & 'O' like on conwaylife.com
+    Cells,
+    /// Life 1.05 or 1.06 (.lif or .life), read as either & written as 1.06
+    Life,
+    /// Golly's macrocell (.mc), a quadtree for patterns too big for the other formats
+    Macrocell,
+}
+impl Format {
+    pub(crate) fn from_path(path: &str) -> Format {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("rle") => Format::Rle,
+            Some("cells") => Format::Cells,
+            Some("lif" | "life") => Format::Life,
+            Some("mc") => Format::Macrocell,
+            _ => Format::Native,
+        }
+    }
+}
+
+/// What a pattern file says about its pattern, besides the cells
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct Metadata {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub comments: Vec<String>,
+}
+
+/// A board read from a file, before it's put on one of the engines
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct SavedBoard {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row
+    pub cells: Vec<CellState>,
+    pub rule: Rule,
+    pub topology: Topology,
+    pub metadata: Metadata,
+}
+impl SavedBoard {
+    /// Puts the cells on a board made by 'new' from the (width, height)
+    fn into_board<B: Board>(self, new: impl FnOnce(usize, usize) -> B) -> Result<B, SaveLoadError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(SaveLoadError::EmptyFile);
+        }
+        let mut board = new(self.width, self.height);
+        board
+            .set_topology(self.topology)
+            .map_err(|_| SaveLoadError::Unsupported)?;
+        board
+            .set_rule(self.rule)
+            .map_err(|_| SaveLoadError::Unsupported)?;
+        board
+            .replace_cells(&self.cells)
+            .map_err(|_| SaveLoadError::EmptyFile)?;
+        Ok(board)
+    }
+}
+
+/// Reads "x,y" pairs from a file. Life 1.05 & 1.06 files are read as patterns
+/// instead, moved so the top left living cell is at (0, 0)
 //noinspection SpellCheckingInspection
 pub fn read_coords_from_file(path: &str) -> Vec<(usize, usize)> {
     match std::fs::read_to_string(path) {
+        Ok(contents) if contents.trim_start().starts_with(LIFE_HEADER) => {
+            match parse_life(&contents).and_then(LifeCells::fit) {
+                Ok(saved) => (0..saved.cells.len())
+                    .filter(|i| saved.cells[*i] == CellState::Alive)
+                    .map(|i| (i % saved.width, i / saved.width))
+                    .collect(),
+                Err(e) => {
+                    eprintln!("Failed to read the pattern from file: {e:?}!");
+                    Vec::new()
+                }
+            }
+        }
         Ok(contents) => text::parse_to_coordinates(contents),
         Err(e) => {
             eprintln!("Failed to read coordinates from file: {e}!");
@@ -14,18 +117,8 @@ pub fn read_coords_from_file(path: &str) -> Vec<(usize, usize)> {
 }
 /// Writes the given game board to the specified file.
 /// This will replace the file if it already exists
-pub fn save_board(path: &str, board: &game::GameBoardOld) {
-    let mut contents: String = String::new();
-
-    for row in &board.space {
-        for cell in row {
-            contents.push((*cell).into());
-        }
-        contents.push('\n');
-    }
-    // a newline in appended to the end of each row, even the last one (this removes it)
-    contents.pop();
-    match std::fs::write(path, contents) {
+pub fn save_board(path: &str, board: &impl Board) {
+    match save_game(board, path) {
         Ok(_) => println!("Saved Successfully!"),
         Err(_) => eprintln!("Error Saving Board"),
     };
@@ -34,74 +127,30 @@ pub fn save_board(path: &str, board: &game::GameBoardOld) {
 /// Loads a game board from a file.
 /// If the file is improperly formatted, it will return an empty board.
 /// Failing to load the board is logged to std err
+#[allow(unused)]
 pub fn load_board_from_file(path: &str) -> game::GameBoardOld {
-    let mut constructed_board: Vec<Vec<CellState>> = Vec::new();
-    let contents = match std::fs::read_to_string(path) {
-        Ok(contents) => contents,
-        Err(_) => {
-            eprintln!("Failed to load board from file");
-            return game::GameBoardOld::new(GAME_X, GAME_Y);
-        }
-    };
-
-    for row in contents.split('\n') {
-        let mut constructed_row: Vec<CellState> = Vec::new();
-        for s in row.chars() {
-            constructed_row.push(match s {
-                ALIVE_STATUS_CHARACTER => CellState::Alive,
-                DEAD_STATUS_CHARACTER => CellState::Dead,
-                _ => {
-                    eprintln!("Error parsing char from file: [{}]", s);
-                    continue;
-                } //Don't push anything on error
-            });
-        }
-        constructed_board.push(constructed_row);
-    }
-
-    game::GameBoardOld {
-        x_max: constructed_board[0].len(),
-        y_max: constructed_board.len(),
-        space: constructed_board, // last to avoid borrowing after move
-    }
+    load(path, game::GameBoardOld::new).unwrap_or_else(|e| {
+        eprintln!("Failed to load board from file: {e:?}");
+        game::GameBoardOld::new(GAME_X, GAME_Y)
+    })
 }
 
-/// Converts a raw text board from conwaylife.com into internal game representation
-#[allow(unused)]
-pub fn convert_wiki_to_board(path: &str) -> game::GameBoardOld {
-    // Load the text from the file, comments are marked w/ "!"
-    let mut file = std::fs::read_to_string(path).unwrap();
-
-    let mut x_max: usize = 0;
-    for row in file.split('\n').filter(|r| !r.contains('!')) {
-        let mut count = 0; // Find the largest row to set the board size
-        row.chars().for_each(|c| {
-            if c == '.' || c == 'O' {
-                count += 1;
-            }
-        });
-        if count > x_max {
-            x_max = count;
-        }
-    }
-
-    let mut board = game::GameBoardOld::new(x_max, file.lines().count());
-    for (y, row) in file.split('\n').filter(|r| !r.contains('!')).enumerate() {
-        for (x, c) in row.chars().enumerate() {
-            match c {
-                '.' => board.set(x, y, CellState::Dead),
-                'O' => board.set(x, y, CellState::Alive),
-                _ => {}
-            }
-        }
+/// Reads a board in one format & saves it in another (each picked by the file's
+/// extension, see [Format]), eg. to turn a .cells file from conwaylife.com into RLE.
+/// The name, author & comments are kept if the new format has room for them.
+/// Fails rather than writing over the file being read
+pub(crate) fn convert(from: &str, to: &str) -> Result<(), SaveLoadError> {
+    let same = match (std::fs::canonicalize(from), std::fs::canonicalize(to)) {
+        (Ok(from), Ok(to)) => from == to,
+        _ => from == to,
+    };
+    if same {
+        return Err(SaveLoadError::SameFile);
     }
-    board
-}
-/// Overwrites a conwaylife.com text board into a game save file
-#[allow(unused)]
-pub fn create_save_from_wiki(path: &str) {
-    let board = convert_wiki_to_board(path); // Load a board from the file
-    save_board(path, &board); // Write the board to the original file
+    let saved = read_board(from)?;
+    let metadata = saved.metadata.clone();
+    let board = saved.into_board(game::Game::new)?;
+    save_with_metadata(&board, &metadata, to)
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -110,6 +159,17 @@ pub(crate) enum SaveLoadError {
     FileWrite,
     FileOpen,
     EmptyFile,
+    InvalidHeader,
+    /// The board can't run the saved rule or topology, or the format can't save them
+    Unsupported,
+    /// The cells couldn't be read, eg. an RLE pattern has a letter that isn't a state
+    InvalidPattern,
+    /// Converting a file would have written over it
+    SameFile,
+    /// The pattern is too big to put on a board, see [MACROCELL_FLATTEN_LIMIT]
+    TooBig,
+    /// The loaded board couldn't be grown to the default size
+    Resize(&'static str),
 }
 impl From<std::fmt::Error> for SaveLoadError {
     fn from(_: std::fmt::Error) -> Self {
@@ -122,50 +182,849 @@ impl From<std::io::Error> for SaveLoadError {
     }
 }
 #[allow(unused)]
-pub(crate) fn save_game(game: &game::Game, path: &str) -> Result<(), SaveLoadError> {
-    use std::fmt::Write as fmtWrite;
-    use std::io::Write as ioWrite;
-
-    let mut f = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(path)
-        .unwrap();
-    let mut s = String::with_capacity(game.y_max * game.x_max);
-
-    for row in game.rows() {
-        for cell in row {
-            write!(s, "{}", *cell)?;
+/// Saves a board in the format picked by the file's extension (see [Format])
+pub(crate) fn save_game(game: &impl Board, path: &str) -> Result<(), SaveLoadError> {
+    save_with_metadata(game, &Metadata::default(), path)
+}
+/// Saves a board like [save_game], along w/ a name, author & comments if the format
+/// has room for them
+pub(crate) fn save_with_metadata(
+    board: &impl Board,
+    metadata: &Metadata,
+    path: &str,
+) -> Result<(), SaveLoadError> {
+    let contents = match Format::from_path(path) {
+        // Loading it would give a bounded board, cells past the viewport would be lost
+        Format::Native if board.topology() == Topology::Unbounded => {
+            return Err(SaveLoadError::Unsupported)
         }
-        writeln!(s)?;
-    }
-    s.pop(); // remove last new line
-    write!(f, "{s}")?;
+        Format::Native => board_to_string(board),
+        Format::Rle => write_rle(board, metadata)?,
+        Format::Cells => write_cells(board, metadata),
+        Format::Life => {
+            let centre = (board.width() / 2, board.height() / 2);
+            write_life(board, metadata, LifeVersion::V106, centre)
+        }
+        Format::Macrocell => {
+            let mut life = HashLife::new(board.rule()).map_err(|_| SaveLoadError::Unsupported)?;
+            let centre = (board.width() / 2, board.height() / 2);
+            for (x, y) in board.live_cells() {
+                let (x, y) = (x as i64 - centre.0 as i64, y as i64 - centre.1 as i64);
+                life.set(x, y, CellState::Alive);
+            }
+            write_macrocell(&life, metadata)
+        }
+    };
+    std::fs::write(path, contents)?;
     Ok(())
 }
 #[allow(unused)]
 pub(crate) fn load_game(path: &str) -> Result<game::Game, SaveLoadError> {
-    let mut f = match std::fs::read_to_string(path) {
-        Ok(f) => f,
-        Err(e) => return Err(SaveLoadError::FileOpen),
+    load(path, game::Game::new)
+}
+/// Loads a game like [load_game], then grows it (keeping it centred) to at least
+/// the default board size (see [load_padded]), so small patterns have room to grow
+#[allow(unused)]
+pub(crate) fn load_game_padded(path: &str) -> Result<game::Game, SaveLoadError> {
+    load_padded(path, game::Game::new)
+}
+/// Loads a save file (in the format picked by its extension, see [Format]) onto any
+/// kind of board, made by 'new' from the (width, height) of the saved board
+pub(crate) fn load<B: Board>(
+    path: &str,
+    new: impl FnOnce(usize, usize) -> B,
+) -> Result<B, SaveLoadError> {
+    read_board(path)?.into_board(new)
+}
+/// Reads a save file w/o putting it on a board
+pub(crate) fn read_board(path: &str) -> Result<SavedBoard, SaveLoadError> {
+    let contents = std::fs::read_to_string(path).map_err(|_| SaveLoadError::FileOpen)?;
+    match Format::from_path(path) {
+        Format::Native => parse_native(&contents),
+        Format::Rle => parse_rle(&contents),
+        Format::Cells => parse_cells(&contents),
+        Format::Life => parse_life(&contents)?.fit(),
+        Format::Macrocell => {
+            let (life, metadata) = parse_macrocell(&contents)?;
+            flatten(&life, metadata)
+        }
+    }
+}
+/// Loads a board like [load_padded], along w/ its name, author & comments (if the
+/// format has them)
+pub(crate) fn load_with_metadata<B: Board>(
+    path: &str,
+    new: impl FnOnce(usize, usize) -> B,
+) -> Result<(B, Metadata), SaveLoadError> {
+    let saved = read_board(path)?;
+    let metadata = saved.metadata.clone();
+    let mut board = saved.into_board(new)?;
+    // Infinite boards only move their viewport
+    if matches!(board.topology(), Topology::Bounded | Topology::Unbounded) {
+        let resize = Resize::at_least(board.size(), (GAME_X, GAME_Y));
+        board.resize(resize).map_err(SaveLoadError::Resize)?;
+    }
+    Ok((board, metadata))
+}
+/// Loads a Life 1.05 or 1.06 file onto a board of 'size' (made by 'new'), w/ the
+/// file's (0, 0) at 'origin' on the board. Fails if a living cell ends up off the board
+pub(crate) fn load_life<B: Board>(
+    path: &str,
+    origin: (i64, i64),
+    size: (usize, usize),
+    new: impl FnOnce(usize, usize) -> B,
+) -> Result<(B, Metadata), SaveLoadError> {
+    let contents = std::fs::read_to_string(path).map_err(|_| SaveLoadError::FileOpen)?;
+    let saved = parse_life(&contents)?.place(origin, size)?;
+    let metadata = saved.metadata.clone();
+    Ok((saved.into_board(new)?, metadata))
+}
+/// Loads a board like [load], then grows it (keeping it centred) to at least the
+/// default board size. Boards w/ other edges are left the size they were saved as,
+/// growing them would change where things wrap around (or where the border is)
+pub(crate) fn load_padded<B: Board>(
+    path: &str,
+    new: impl FnOnce(usize, usize) -> B,
+) -> Result<B, SaveLoadError> {
+    load_with_metadata(path, new).map(|(board, _)| board)
+}
+/// Loads the living part of a saved board as a pattern, eg. to add it to the catalogue
+pub(crate) fn load_pattern(path: &str) -> Result<Pattern, SaveLoadError> {
+    let mut game = load_game(path)?;
+    game.crop_to_live().map_err(|_| SaveLoadError::EmptyFile)?;
+    let area = Rect {
+        x: 0,
+        y: 0,
+        width: game.x_max,
+        height: game.y_max,
+    };
+    game.copy(area).map_err(|_| SaveLoadError::EmptyFile)
+}
+
+/// Reads this program's own save files. Rows shorter than the longest one are filled
+/// out w/ dead cells
+fn parse_native(contents: &str) -> Result<SavedBoard, SaveLoadError> {
+    let (header, rows) = split_header(contents);
+    if rows.is_empty() {
+        return Err(SaveLoadError::EmptyFile);
+    }
+    let topology = read_header(&header, TOPOLOGY_HEADER)?;
+    let rule = read_header(&header, RULE_HEADER)?;
+
+    let rows: Vec<Vec<CellState>> = rows
+        .iter()
+        .map(|row| row.chars().filter_map(CellState::from_char).collect())
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let height = rows.len();
+    let cells = rows
+        .into_iter()
+        .flat_map(|mut row| {
+            row.resize(width, CellState::Dead);
+            row
+        })
+        .collect();
+    Ok(SavedBoard {
+        width,
+        height,
+        cells,
+        rule,
+        topology,
+        metadata: Metadata::default(),
+    })
+}
+
+/// Reads an RLE pattern, eg.
+/// ```text
+/// #N Glider
+/// #C The smallest spaceship
+/// x = 3, y = 3, rule = B3/S23
+/// bo$2bo$3o!
+/// ```
+/// Runs of cells are a count (1 if it's left out) & a state: b or o in 2 state rules,
+/// or . for dead & A to X (w/ p to y in front for states past 24) in bigger ones.
+/// $ ends a row & ! ends the pattern. Edges that wrap are written like Golly does,
+/// after the rule: "B3/S23:T20,10" for a torus, "K20*,10" for a Klein bottle & "C20,10"
+/// for a cross-surface
+pub(crate) fn parse_rle(contents: &str) -> Result<SavedBoard, SaveLoadError> {
+    let mut metadata = Metadata::default();
+    let mut lines = contents.lines().map(str::trim).filter(|l| !l.is_empty());
+    let mut header = None;
+    for line in lines.by_ref() {
+        let Some(comment) = line.strip_prefix('#') else {
+            header = Some(line);
+            break;
+        };
+        let mut chars = comment.chars();
+        let kind = chars.next();
+        let text = chars.as_str().trim().to_string();
+        match kind {
+            Some('N') => metadata.name = Some(text),
+            Some('O') => metadata.author = Some(text),
+            Some('C' | 'c') => metadata.comments.push(text),
+            _ => {} // eg. positions, which don't matter on a board w/ edges
+        }
+    }
+    let header = header.ok_or(SaveLoadError::EmptyFile)?;
+
+    // "x = 3, y = 3, rule = B3/S23", the rule can have commas in it so it's split off first
+    // Found in the bytes (ignoring ASCII case) so 'i' is always a character boundary
+    let rule_at = header
+        .as_bytes()
+        .windows(RULE_HEADER.len())
+        .position(|w| w.eq_ignore_ascii_case(RULE_HEADER.as_bytes()));
+    let (size, rule) = match rule_at {
+        Some(i) => (&header[..i], Some(&header[i + RULE_HEADER.len()..])),
+        None => (header, None),
+    };
+    let (mut width, mut height): (Option<usize>, Option<usize>) = (None, None);
+    for part in size.split(',').filter(|p| !p.trim().is_empty()) {
+        let (key, value) = part.split_once('=').ok_or(SaveLoadError::InvalidHeader)?;
+        let value = value
+            .trim()
+            .parse()
+            .map_err(|_| SaveLoadError::InvalidHeader)?;
+        match key.trim() {
+            "x" => width = Some(value),
+            "y" => height = Some(value),
+            _ => return Err(SaveLoadError::InvalidHeader),
+        }
+    }
+    let (Some(width), Some(height)) = (width, height) else {
+        return Err(SaveLoadError::InvalidHeader);
+    };
+    // Golly writes an empty pattern as "x = 0, y = 0", it's read as a single dead cell
+    let len = board_len(width.max(1), height.max(1))?;
+    // Golly puts the edges after the rule, eg. "B3/S23:T20,10" for a 20 by 10 torus
+    let rule = rule.map(|r| r.trim().trim_start_matches('='));
+    let (rule, edges) = match rule.and_then(|r| r.split_once(':')) {
+        Some((rule, edges)) => (Some(rule), Some(edges.trim())),
+        None => (rule, None),
+    };
+    let rule: Rule = match rule {
+        Some(rule) => rule
+            .trim()
+            .parse()
+            .map_err(|_| SaveLoadError::InvalidHeader)?,
+        None => Rule::default(),
+    };
+    // A Klein bottle has a '*' after the size of the edges joined w/ a twist, only
+    // twisted top & bottom edges ("K20*,10") can be run
+    let twisted = edges
+        .and_then(|e| e.split(',').next())
+        .is_some_and(|w| w.ends_with('*'));
+    let topology = match edges.and_then(|e| e.chars().next()) {
+        None | Some('P' | 'p') => Topology::Bounded,
+        Some('T' | 't') => Topology::Torus,
+        Some('K' | 'k') if twisted => Topology::KleinBottle,
+        Some('C' | 'c') => Topology::CrossSurface,
+        Some(_) => return Err(SaveLoadError::Unsupported),
+    };
+    let edge_size = edges.map(edge_size).transpose()?;
+
+    let states = rule.states();
+    let mut cells = vec![CellState::Dead; len];
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut count: Option<usize> = None;
+    let mut prefix = None;
+    for c in lines.flat_map(str::chars) {
+        match c {
+            '!' => break,
+            '0'..='9' => {
+                let digit = c as usize - '0' as usize;
+                count = count
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(digit));
+                if count.is_none() {
+                    return Err(SaveLoadError::InvalidPattern);
+                }
+            }
+            '$' => {
+                y = y.saturating_add(count.take().unwrap_or(1));
+                x = 0;
+            }
+            'p'..='y' if prefix.is_none() => prefix = Some(c),
+            c if c.is_whitespace() => {}
+            c => {
+                let index = rle_state(prefix.take(), c)
+                    .filter(|i| *i < states)
+                    .ok_or(SaveLoadError::InvalidPattern)?;
+                let cell = CellState::from_state_index(index, states);
+                let run = count.take().unwrap_or(1);
+                if cell != CellState::Dead {
+                    if y >= height || x.saturating_add(run) > width {
+                        return Err(SaveLoadError::InvalidPattern);
+                    }
+                    let start = y * width + x;
+                    cells[start..start + run].fill(cell);
+                }
+                x = x.saturating_add(run);
+            }
+        }
+    }
+    // Any living cell on an empty pattern was already refused as off the board
+    let (width, height) = (width.max(1), height.max(1));
+    // The edges are the size of the board, w/ the pattern in the middle
+    let (cells, (width, height)) = match edge_size {
+        Some((w, h)) if (w, h) != (width, height) => {
+            if w < width || h < height {
+                return Err(SaveLoadError::InvalidPattern);
+            }
+            board_len(w, h)?;
+            Resize::To(w, h, Anchor::Center)
+                .apply(&cells, (width, height))
+                .map_err(|_| SaveLoadError::InvalidPattern)?
+        }
+        _ => (cells, (width, height)),
+    };
+    Ok(SavedBoard {
+        width,
+        height,
+        cells,
+        rule,
+        topology,
+        metadata,
+    })
+}
+/// The (width, height) of the edges after an RLE rule, eg. "T20,10" or "K20*,10".
+/// Golly's edges that go on forever (a size of 0) & shifted tori can't be run
+fn edge_size(edges: &str) -> Result<(usize, usize), SaveLoadError> {
+    // The first character is the kind of edges, which was already checked
+    let size = edges.get(1..).ok_or(SaveLoadError::InvalidHeader)?;
+    if size.contains(['+', '-']) {
+        return Err(SaveLoadError::Unsupported);
+    }
+    let (width, height) = size.split_once(',').ok_or(SaveLoadError::InvalidHeader)?;
+    let parse = |n: &str| {
+        n.trim()
+            .trim_end_matches('*')
+            .parse::<usize>()
+            .map_err(|_| SaveLoadError::InvalidHeader)
+    };
+    match (parse(width)?, parse(height)?) {
+        (0, _) | (_, 0) => Err(SaveLoadError::Unsupported),
+        size => Ok(size),
+    }
+}
+/// Writes a board as an RLE pattern (see [parse_rle]), w/ lines no longer than 70
+/// characters. Dead cells at the ends of rows & the bottom of the board are left off.
+/// Fails if the board has a border of living cells, which Golly can't write down
+pub(crate) fn write_rle(board: &impl Board, metadata: &Metadata) -> Result<String, SaveLoadError> {
+    let (width, height) = board.size();
+    let edges = match board.topology() {
+        // W/o edges Golly runs the pattern on an infinite board too
+        Topology::Bounded | Topology::Unbounded => String::new(),
+        Topology::Torus => format!(":T{width},{height}"),
+        // The '*' twists the top & bottom edges
+        Topology::KleinBottle => format!(":K{width}*,{height}"),
+        Topology::CrossSurface => format!(":C{width},{height}"),
+        Topology::AliveBorder => return Err(SaveLoadError::Unsupported),
+    };
+    let mut contents = String::new();
+    if let Some(name) = &metadata.name {
+        contents.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &metadata.author {
+        contents.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &metadata.comments {
+        contents.push_str(&format!("#C {comment}\n"));
+    }
+    let rule = board.rule();
+    contents.push_str(&format!(
+        "x = {width}, y = {height}, rule = {rule}{edges}\n"
+    ));
+
+    let run = |count: usize, tag: &str| match count {
+        1 => tag.to_string(),
+        _ => format!("{count}{tag}"),
     };
-    if f.lines().count() < 1 {
+    let mut runs = Vec::new();
+    // Rows ended since the last living cell, only written once there's another one
+    let mut ended = 0;
+    for row in board.rows() {
+        let Some(end) = row.iter().rposition(|c| *c != CellState::Dead) else {
+            ended += 1;
+            continue;
+        };
+        let tags: Vec<String> = row.iter().map(|c| rle_tag(*c, rule.states())).collect();
+        if ended > 0 {
+            runs.push(run(ended, "$"));
+        }
+        let mut start = 0;
+        while start <= end {
+            let length = tags[start..=end]
+                .iter()
+                .take_while(|t| **t == tags[start])
+                .count();
+            runs.push(run(length, &tags[start]));
+            start += length;
+        }
+        ended = 1;
+    }
+    runs.push("!".to_string());
+
+    let mut line = String::new();
+    for run in runs {
+        if line.len() + run.len() > RLE_LINE_LENGTH {
+            contents.push_str(&line);
+            contents.push('\n');
+            line.clear();
+        }
+        line.push_str(&run);
+    }
+    contents.push_str(&line);
+    contents.push('\n');
+    Ok(contents)
+}
+/// Reads a plain text pattern, eg.
+/// ```text
+/// !Name: Glider
+/// !The smallest spaceship
+/// .O
+/// ..O
+/// OOO
+/// ```
+/// 'O' (or '*') is alive & '.' is dead. Lines starting w/ '!' are the name, author
+/// ("!Author: ...") or comments. Short rows are filled out w/ dead cells
+pub(crate) fn parse_cells(contents: &str) -> Result<SavedBoard, SaveLoadError> {
+    let mut metadata = Metadata::default();
+    let mut rows = Vec::new();
+    for line in contents.lines().map(str::trim_end) {
+        let Some(comment) = line.strip_prefix('!') else {
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    'O' | '*' => Ok(CellState::Alive),
+                    '.' => Ok(CellState::Dead),
+                    _ => Err(SaveLoadError::InvalidPattern),
+                })
+                .collect::<Result<Vec<CellState>, _>>()?;
+            rows.push(row);
+            continue;
+        };
+        if let Some(name) = comment.strip_prefix("Name:") {
+            metadata.name = Some(name.trim().to_string());
+        } else if let Some(author) = comment.strip_prefix("Author:") {
+            metadata.author = Some(author.trim().to_string());
+        } else {
+            metadata.comments.push(comment.trim().to_string());
+        }
+    }
+    // Blank lines are empty rows, but not at the end of the file
+    while rows.last().is_some_and(Vec::is_empty) {
+        rows.pop();
+    }
+    if rows.is_empty() {
         return Err(SaveLoadError::EmptyFile);
     }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    let height = rows.len();
+    let cells = rows
+        .into_iter()
+        .flat_map(|mut row| {
+            row.resize(width, CellState::Dead);
+            row
+        })
+        .collect();
+    Ok(SavedBoard {
+        width,
+        height,
+        cells,
+        rule: Rule::default(),
+        topology: Topology::default(),
+        metadata,
+    })
+}
+/// Writes a board as a plain text pattern (see [parse_cells]). Only living cells are
+/// written as 'O', anything else (like dying cells) is dead
+pub(crate) fn write_cells(board: &impl Board, metadata: &Metadata) -> String {
+    let mut contents = String::new();
+    if let Some(name) = &metadata.name {
+        contents.push_str(&format!("!Name: {name}\n"));
+    }
+    if let Some(author) = &metadata.author {
+        contents.push_str(&format!("!Author: {author}\n"));
+    }
+    for comment in &metadata.comments {
+        contents.push_str(&format!("!{comment}\n"));
+    }
+    for row in board.rows() {
+        contents.extend(row.iter().map(|c| match c {
+            CellState::Alive => 'O',
+            _ => '.',
+        }));
+        contents.push('\n');
+    }
+    contents
+}
 
-    let y_max = f.lines().count();
-    let x_max = f.lines().next().unwrap().chars().count();
+/// The versions of the Life format, see [parse_life]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum LifeVersion {
+    /// Blocks of rows of '.' & '*'
+    V105,
+    /// A list of living cells
+    V106,
+}
+impl std::fmt::Display for LifeVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LifeVersion::V105 => write!(f, "1.05"),
+            LifeVersion::V106 => write!(f, "1.06"),
+        }
+    }
+}
+impl std::str::FromStr for LifeVersion {
+    type Err = SaveLoadError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1.05" => Ok(LifeVersion::V105),
+            "1.06" => Ok(LifeVersion::V106),
+            _ => Err(SaveLoadError::InvalidHeader),
+        }
+    }
+}
 
-    let characters: Vec<CellState> = f
-        .chars()
-        .filter_map(|c| match c {
-            ALIVE_STATUS_CHARACTER => Some(CellState::Alive),
-            DEAD_STATUS_CHARACTER => Some(CellState::Dead),
-            _ => None,
+/// The living cells of a Life 1.05 or 1.06 file. There's no board size, cells are
+/// anywhere around (0, 0) (usually the middle of the pattern), so they have to be
+/// placed on a board before they're loaded
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct LifeCells {
+    /// (x, y) of every living cell, w/ y going down
+    pub cells: Vec<(i64, i64)>,
+    pub rule: Rule,
+    pub metadata: Metadata,
+}
+impl LifeCells {
+    /// Puts the cells on a board of 'size', w/ (0, 0) at 'origin' on the board.
+    /// Fails if any living cell ends up off the board, or the board would be bigger
+    /// than [MACROCELL_FLATTEN_LIMIT]
+    pub(crate) fn place(
+        self,
+        origin: (i64, i64),
+        (width, height): (usize, usize),
+    ) -> Result<SavedBoard, SaveLoadError> {
+        let on_board = |n: i64, offset: i64, len: usize| {
+            n.checked_add(offset)
+                .and_then(|n| usize::try_from(n).ok())
+                .filter(|n| *n < len)
+        };
+        let len = board_len(width, height)?;
+        let mut cells = vec![CellState::Dead; len];
+        for (x, y) in &self.cells {
+            match (
+                on_board(*x, origin.0, width),
+                on_board(*y, origin.1, height),
+            ) {
+                (Some(x), Some(y)) => cells[y * width + x] = CellState::Alive,
+                _ => return Err(SaveLoadError::InvalidPattern),
+            }
+        }
+        Ok(SavedBoard {
+            width,
+            height,
+            cells,
+            rule: self.rule,
+            topology: Topology::default(),
+            metadata: self.metadata,
         })
+    }
+    /// Puts the cells on the smallest board that holds them all
+    pub(crate) fn fit(self) -> Result<SavedBoard, SaveLoadError> {
+        let xs = self.cells.iter().map(|(x, _)| *x);
+        let ys = self.cells.iter().map(|(_, y)| *y);
+        let (Some(x0), Some(x1), Some(y0), Some(y1)) =
+            (xs.clone().min(), xs.max(), ys.clone().min(), ys.max())
+        else {
+            return Err(SaveLoadError::EmptyFile);
+        };
+        let span = |min: i64, max: i64| {
+            max.checked_sub(min)
+                .and_then(|n| usize::try_from(n).ok())
+                .and_then(|n| n.checked_add(1))
+                .ok_or(SaveLoadError::TooBig)
+        };
+        let size = (span(x0, x1)?, span(y0, y1)?);
+        let origin = (0i64.checked_sub(x0), 0i64.checked_sub(y0));
+        let (Some(ox), Some(oy)) = origin else {
+            return Err(SaveLoadError::InvalidPattern);
+        };
+        self.place((ox, oy), size)
+    }
+}
+
+/// Reads a Life 1.05 or 1.06 file, the version is on the first line. 1.06 files
+/// have the (x, y) of a living cell on each line, eg. a glider
+/// ```text
+/// #Life 1.06
+/// 0 -1
+/// 1 0
+/// -1 1
+/// 0 1
+/// 1 1
+/// ```
+/// 1.05 files have blocks of rows of '.' & '*', each after a "#P x y" line w/ the
+/// position of its top left corner. In both, "#D" lines are comments (see
+/// [write_life] for the name & author), "#R" has the rule (eg. "#R 23/3", survival
+/// first) & "#N" is the normal Life rule
+pub(crate) fn parse_life(contents: &str) -> Result<LifeCells, SaveLoadError> {
+    let mut lines = contents.lines().map(str::trim).filter(|l| !l.is_empty());
+    let version: LifeVersion = lines
+        .next()
+        .and_then(|l| l.strip_prefix(LIFE_HEADER))
+        .ok_or(SaveLoadError::InvalidHeader)?
+        .parse()?;
+    let pair = |line: &str| -> Result<(i64, i64), SaveLoadError> {
+        let mut numbers = line.split_whitespace().map(str::parse);
+        match (numbers.next(), numbers.next(), numbers.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+            _ => Err(SaveLoadError::InvalidPattern),
+        }
+    };
+
+    let mut life = LifeCells {
+        cells: Vec::new(),
+        rule: Rule::default(),
+        metadata: Metadata::default(),
+    };
+    // Where the next row of a 1.05 block starts
+    let mut row = (0i64, 0i64);
+    for line in lines {
+        if let Some(line) = line.strip_prefix('#') {
+            let mut chars = line.chars();
+            let kind = chars.next();
+            let text = chars.as_str().trim();
+            match kind {
+                Some('D') => match (text.strip_prefix("Name:"), text.strip_prefix("Author:")) {
+                    (Some(name), _) => life.metadata.name = Some(name.trim().to_string()),
+                    (_, Some(author)) => life.metadata.author = Some(author.trim().to_string()),
+                    _ => life.metadata.comments.push(text.to_string()),
+                },
+                Some('N') => life.rule = Rule::default(),
+                Some('R') => life.rule = text.parse().map_err(|_| SaveLoadError::InvalidHeader)?,
+                Some('P') => row = pair(text)?,
+                _ => return Err(SaveLoadError::InvalidHeader),
+            }
+            continue;
+        }
+        if version == LifeVersion::V106 {
+            life.cells.push(pair(line)?);
+            continue;
+        }
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '*' | 'O' => {
+                    let x = row.0.checked_add(x as i64);
+                    life.cells
+                        .push((x.ok_or(SaveLoadError::InvalidPattern)?, row.1));
+                }
+                '.' => {}
+                _ => return Err(SaveLoadError::InvalidPattern),
+            }
+        }
+        row.1 = row.1.checked_add(1).ok_or(SaveLoadError::InvalidPattern)?;
+    }
+    Ok(life)
+}
+/// Writes the living cells of a board as a Life file (see [parse_life]), w/ 'origin'
+/// on the board written as (0, 0). 1.05 files get the name & author as "#D Name: ..."
+/// & "#D Author: ..." lines, the comments & the rule, & the cells in one block. 1.06
+/// files only have the cells, so the rule & comments are lost
+pub(crate) fn write_life(
+    board: &impl Board,
+    metadata: &Metadata,
+    version: LifeVersion,
+    origin: (usize, usize),
+) -> String {
+    let mut contents = format!("{LIFE_HEADER} {version}\n");
+    let cells: Vec<(i64, i64)> = board
+        .live_cells()
+        .into_iter()
+        .map(|(x, y)| (x as i64 - origin.0 as i64, y as i64 - origin.1 as i64))
         .collect();
+    if version == LifeVersion::V106 {
+        for (x, y) in cells {
+            contents.push_str(&format!("{x} {y}\n"));
+        }
+        return contents;
+    }
+
+    if let Some(name) = &metadata.name {
+        contents.push_str(&format!("#D Name: {name}\n"));
+    }
+    if let Some(author) = &metadata.author {
+        contents.push_str(&format!("#D Author: {author}\n"));
+    }
+    for comment in &metadata.comments {
+        contents.push_str(&format!("#D {comment}\n"));
+    }
+    // Rules that don't fit the survival/birth form are written w/ B & S, which is
+    // still read back
+    let rule = board.rule();
+    match rule.survival_birth() {
+        _ if rule == Rule::default() => contents.push_str("#N\n"),
+        Some(rule) => contents.push_str(&format!("#R {rule}\n")),
+        None => contents.push_str(&format!("#R {rule}\n")),
+    }
+    let (Some(x0), Some(x1)) = (
+        cells.iter().map(|(x, _)| *x).min(),
+        cells.iter().map(|(x, _)| *x).max(),
+    ) else {
+        return contents;
+    };
+    let (y0, y1) = (cells[0].1, cells[cells.len() - 1].1); // sorted by row
+    contents.push_str(&format!("#P {x0} {y0}\n"));
+    let mut rows = vec![vec!['.'; (x1 - x0 + 1) as usize]; (y1 - y0 + 1) as usize];
+    for (x, y) in cells {
+        rows[(y - y0) as usize][(x - x0) as usize] = '*';
+    }
+    for row in rows {
+        // Dots at the end of a row are left off, but empty rows need one
+        let row: String = row.into_iter().collect();
+        let trimmed = row.trim_end_matches('.');
+        contents.push_str(if trimmed.is_empty() { "." } else { trimmed });
+        contents.push('\n');
+    }
+    contents
+}
 
-    let mut game = game::Game::new(x_max, y_max);
-    game.replace_buffer(characters).unwrap();
-    Ok(game)
+/// Reads a Golly macrocell file (see [HashLife::from_macrocell]), w/ the name, author
+/// & comments on "#N", "#O" & "#C" lines like in RLE files
+pub(crate) fn parse_macrocell(contents: &str) -> Result<(HashLife, Metadata), SaveLoadError> {
+    let first = contents.lines().next().unwrap_or_default();
+    if !first.trim_start().starts_with(MACROCELL_HEADER) {
+        return Err(SaveLoadError::InvalidHeader);
+    }
+    let mut metadata = Metadata::default();
+    for line in contents.lines().filter_map(|l| l.trim().strip_prefix('#')) {
+        let mut chars = line.chars();
+        let kind = chars.next();
+        let text = chars.as_str().trim().to_string();
+        match kind {
+            Some('N') => metadata.name = Some(text),
+            Some('O') => metadata.author = Some(text),
+            Some('C') => metadata.comments.push(text),
+            _ => {} // the rule & generation are read by HashLife
+        }
+    }
+    let life = HashLife::from_macrocell(contents).map_err(|_| SaveLoadError::InvalidPattern)?;
+    Ok((life, metadata))
+}
+/// Writes a macrocell file, see [parse_macrocell]
+pub(crate) fn write_macrocell(life: &HashLife, metadata: &Metadata) -> String {
+    let mut contents = format!(
+        "{MACROCELL_HEADER} ({} {})\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    if let Some(name) = &metadata.name {
+        contents.push_str(&format!("#N {name}\n"));
+    }
+    if let Some(author) = &metadata.author {
+        contents.push_str(&format!("#O {author}\n"));
+    }
+    for comment in &metadata.comments {
+        contents.push_str(&format!("#C {comment}\n"));
+    }
+    contents.push_str(&life.to_macrocell());
+    contents
+}
+/// Puts the living cells of a HashLife board on the smallest board that holds them,
+/// as long as that's within [MACROCELL_FLATTEN_LIMIT]
+fn flatten(life: &HashLife, metadata: Metadata) -> Result<SavedBoard, SaveLoadError> {
+    let ((x0, y0), (x1, y1)) = life.bounding_box().ok_or(SaveLoadError::EmptyFile)?;
+    let (width, height) = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+    if width > MACROCELL_FLATTEN_LIMIT || height > MACROCELL_FLATTEN_LIMIT {
+        return Err(SaveLoadError::TooBig);
+    }
+    let game = life.to_game((x0, y0), width, height);
+    Ok(SavedBoard {
+        width,
+        height,
+        cells: game.cells().to_vec(),
+        rule: life.rule(),
+        topology: Topology::default(),
+        metadata,
+    })
+}
+
+/// How many cells a board of the size has, as long as it's within
+/// [MACROCELL_FLATTEN_LIMIT]
+fn board_len(width: usize, height: usize) -> Result<usize, SaveLoadError> {
+    if width > MACROCELL_FLATTEN_LIMIT || height > MACROCELL_FLATTEN_LIMIT {
+        return Err(SaveLoadError::TooBig);
+    }
+    width.checked_mul(height).ok_or(SaveLoadError::TooBig)
+}
+/// The state number of an RLE letter, see [parse_rle]
+fn rle_state(prefix: Option<char>, c: char) -> Option<u8> {
+    let letter = |c: char| ('A'..='X').contains(&c).then(|| c as u32 - 'A' as u32 + 1);
+    let index = match (prefix, c) {
+        (None, 'b' | '.') => 0,
+        (None, 'o') => 1,
+        (None, c) => letter(c)?,
+        (Some(p), c) => (p as u32 - 'p' as u32 + 1) * 24 + letter(c)?,
+    };
+    index.try_into().ok()
+}
+/// The RLE letters for a cell, see [parse_rle]
+fn rle_tag(cell: CellState, states: u8) -> String {
+    if states <= 2 {
+        return match cell {
+            CellState::Alive => "o".to_string(),
+            _ => "b".to_string(),
+        };
+    }
+    match cell.state_index(states) {
+        0 => ".".to_string(),
+        i @ 1..=24 => char::from(b'A' + i - 1).to_string(),
+        i => {
+            let (prefix, letter) = ((i - 25) / 24, (i - 25) % 24);
+            format!("{}{}", char::from(b'p' + prefix), char::from(b'A' + letter))
+        }
+    }
+}
+
+/// Writes a board the way it's saved: the header lines, then each row of cells
+fn board_to_string(board: &impl Board) -> String {
+    let mut contents = format!(
+        "{HEADER_START}{RULE_HEADER} {}\n{HEADER_START}{TOPOLOGY_HEADER} {}\n",
+        board.rule(),
+        board.topology()
+    );
+    for row in board.rows() {
+        contents.extend(row.into_iter().map(char::from));
+        contents.push('\n');
+    }
+    // a newline in appended to the end of each row, even the last one (this removes it)
+    contents.pop();
+    contents
+}
+/// Splits the contents of a save file into the header lines (w/o the leading '#')
+/// and the rows of the board. Blank lines are dropped
+fn split_header(contents: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut header = Vec::new();
+    let mut rows = Vec::new();
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        match line.strip_prefix(HEADER_START) {
+            Some(h) => header.push(h.trim()),
+            None => rows.push(line),
+        }
+    }
+    (header, rows)
+}
+/// Finds a value (eg. the topology) in the header lines of a save file.
+/// Saves without one (eg. older saves) get the default
+fn read_header<T: str::FromStr + Default>(header: &[&str], key: &str) -> Result<T, SaveLoadError> {
+    for line in header {
+        if let Some(value) = line.strip_prefix(key) {
+            return value
+                .trim()
+                .parse()
+                .map_err(|_| SaveLoadError::InvalidHeader);
+        }
+    }
+    Ok(T::default())
 }
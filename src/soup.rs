@@ -0,0 +1,239 @@
+use std::fmt::Formatter;
+
+use crate::game::CellState;
+use crate::pattern::{PasteMode, Pattern, Rect};
+
+/// A small, fast random number generator (SplitMix64). The same seed always gives
+/// the same numbers, on every platform, so soups can be made again from their seed
+#[derive(Debug, Clone)]
+pub struct Random(u64);
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+    /// A number in 0..1
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    /// True w/ the given probability
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+}
+
+/// The symmetry of a soup, named like apgsearch's symmetries. C is rotational (C2
+/// is half turns, C4 quarter turns), D adds mirrors. Symmetries w/ quarter turns or
+/// diagonal mirrors need a square region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Symmetry {
+    /// No symmetry
+    #[default]
+    C1,
+    C2,
+    C4,
+    /// Mirrored left to right
+    D2,
+    /// Mirrored along the main diagonal
+    D2x,
+    /// Mirrored left to right & top to bottom
+    D4,
+    /// Mirrored along both diagonals
+    D4x,
+    /// Every rotation & mirror
+    D8,
+}
+impl Symmetry {
+    fn needs_square(&self) -> bool {
+        matches!(
+            self,
+            Symmetry::C4 | Symmetry::D2x | Symmetry::D4x | Symmetry::D8
+        )
+    }
+    /// Every position (x, y) is mapped to by the symmetry, in a (w, h) rectangle
+    fn images(&self, (x, y): (usize, usize), (w, h): (usize, usize)) -> Vec<(usize, usize)> {
+        let half_turn = (w - 1 - x, h - 1 - y);
+        let mirror_x = (w - 1 - x, y);
+        let mirror_y = (x, h - 1 - y);
+        // these only make sense on squares
+        let quarter_turns = || [(h - 1 - y, x), (y, w - 1 - x)];
+        let diagonals = || [(y, x), (h - 1 - y, w - 1 - x)];
+        match self {
+            Symmetry::C1 => vec![(x, y)],
+            Symmetry::C2 => vec![(x, y), half_turn],
+            Symmetry::C4 => [[(x, y), half_turn], quarter_turns()].concat(),
+            Symmetry::D2 => vec![(x, y), mirror_x],
+            Symmetry::D2x => vec![(x, y), (y, x)],
+            Symmetry::D4 => vec![(x, y), half_turn, mirror_x, mirror_y],
+            Symmetry::D4x => [[(x, y), half_turn], diagonals()].concat(),
+            Symmetry::D8 => [
+                [(x, y), half_turn],
+                [mirror_x, mirror_y],
+                quarter_turns(),
+                diagonals(),
+            ]
+            .concat(),
+        }
+    }
+}
+impl std::fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+impl std::str::FromStr for Symmetry {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "C1" | "NONE" => Ok(Symmetry::C1),
+            "C2" => Ok(Symmetry::C2),
+            "C4" => Ok(Symmetry::C4),
+            "D2" => Ok(Symmetry::D2),
+            "D2X" => Ok(Symmetry::D2x),
+            "D4" => Ok(Symmetry::D4),
+            "D4X" => Ok(Symmetry::D4x),
+            "D8" => Ok(Symmetry::D8),
+            _ => Err("unknown symmetry, expected C1, C2, C4, D2, D2x, D4, D4x or D8"),
+        }
+    }
+}
+
+/// Makes a random (width, height) pattern, where each cell is alive w/ a chance of
+/// 'density'. Cells the symmetry maps onto each other are the same
+pub fn random_pattern(
+    (width, height): (usize, usize),
+    density: f64,
+    seed: u64,
+    symmetry: Symmetry,
+) -> Result<Pattern, &'static str> {
+    if !(0.0..=1.0).contains(&density) {
+        return Err("The density has to be between 0 and 1");
+    }
+    if symmetry.needs_square() && width != height {
+        return Err("That symmetry needs a square region");
+    }
+    let mut random = Random::new(seed);
+    let mut cells: Vec<CellState> = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            // Only the first cell (in reading order) of each group of symmetric
+            // cells is random, the rest copy it
+            let first = symmetry
+                .images((x, y), (width, height))
+                .into_iter()
+                .min_by_key(|(x, y)| (*y, *x))
+                .unwrap();
+            cells.push(match first == (x, y) {
+                true if random.chance(density) => CellState::Alive,
+                true => CellState::Dead,
+                false => cells[first.1 * width + first.0],
+            });
+        }
+    }
+    Pattern::new(width, height, cells)
+}
+
+/// Everything needed to make a soup again: where it goes, how full it is, its seed
+/// & its symmetry. Written (& parsed) like "seed=1234 density=0.5 symmetry=C2 region=0,0,9,9"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Soup {
+    /// The corners of the region (both inclusive), None for the whole board
+    pub region: Option<Rect>,
+    pub density: f64,
+    pub seed: u64,
+    pub symmetry: Symmetry,
+}
+impl Soup {
+    /// A soup filling the whole board half full, seeded from the clock
+    pub fn new() -> Self {
+        Soup {
+            region: None,
+            density: 0.5,
+            seed: clock_seed(),
+            symmetry: Symmetry::default(),
+        }
+    }
+    /// Fills the soup's region of a board stored row by row w/ random cells.
+    /// Anything past the edges of the board is left off, the cells that are on it are
+    /// the same as they'd be on a board big enough for the whole region
+    pub fn paste_onto(&self, cells: &mut [CellState], width: usize) -> Result<(), &'static str> {
+        let height = cells.len() / width.max(1);
+        let region = self.region.unwrap_or(Rect {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        });
+        if region.clip(width, height).is_none() {
+            return Err("Can't randomize, the region isn't on the board");
+        }
+        let size = (region.width, region.height);
+        let soup = random_pattern(size, self.density, self.seed, self.symmetry)?;
+        soup.paste_onto(cells, width, (region.x, region.y), PasteMode::Overwrite);
+        Ok(())
+    }
+}
+impl Default for Soup {
+    fn default() -> Self {
+        Soup::new()
+    }
+}
+impl std::fmt::Display for Soup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "seed={} density={} symmetry={}",
+            self.seed, self.density, self.symmetry
+        )?;
+        if let Some(r) = self.region {
+            let (x1, y1) = (r.x + r.width - 1, r.y + r.height - 1);
+            write!(f, " region={},{},{x1},{y1}", r.x, r.y)?;
+        }
+        Ok(())
+    }
+}
+impl std::str::FromStr for Soup {
+    type Err = &'static str;
+    /// Anything left out gets the default from [Soup::new]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut soup = Soup::new();
+        for setting in s.split_whitespace() {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or("Expected settings like seed=1234")?;
+            match key.to_lowercase().as_str() {
+                "seed" => soup.seed = value.parse().map_err(|_| "Couldn't parse the seed")?,
+                "density" => {
+                    soup.density = value.parse().map_err(|_| "Couldn't parse the density")?
+                }
+                "symmetry" => soup.symmetry = value.parse()?,
+                "region" => {
+                    let corners: Vec<usize> = value
+                        .split(',')
+                        .map(|n| n.trim().parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| "Couldn't parse the region, expected x,y,x,y")?;
+                    let [x0, y0, x1, y1] = corners[..] else {
+                        return Err("Couldn't parse the region, expected x,y,x,y");
+                    };
+                    soup.region = Some(Rect::from_corners((x0, y0), (x1, y1)));
+                }
+                _ => return Err("Unknown setting, expected seed, density, symmetry or region"),
+            }
+        }
+        Ok(soup)
+    }
+}
+
+/// A seed from the current time, for when the user doesn't pick one
+pub(crate) fn clock_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::game::{CellState, Game};
+use crate::rule::Rule;
+
+/// An (effectively) infinite board: only the coordinates of the living cells are
+/// stored, so patterns can travel as far as they want without hitting an edge.
+///
+/// Coordinates are signed, and the board has no edges or size. To look at part of
+/// it, pick a viewport with [SparseGame::rows] or [SparseGame::to_game].
+#[derive(Debug, Clone, Default)]
+#[allow(unused)]
+pub struct SparseGame {
+    alive: HashSet<(i64, i64)>,
+    rule: Rule,
+    generation: u64,
+}
+#[allow(unused)]
+impl SparseGame {
+    pub fn new() -> Self {
+        SparseGame::default()
+    }
+    /// Copies every living cell of the board, with the board's (0, 0) placed at 'origin'.
+    /// The board's rule is kept, unless it can't be run here (see [SparseGame::set_rule])
+    pub fn from_game(game: &Game, origin: (i64, i64)) -> Self {
+        let mut sparse = SparseGame::new();
+        let _ = sparse.set_rule(game.rule());
+        for (y, row) in game.rows().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if *cell == CellState::Alive {
+                    sparse
+                        .alive
+                        .insert((origin.0 + x as i64, origin.1 + y as i64));
+                }
+            }
+        }
+        sparse
+    }
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+    /// Changes the rule used for stepping. Rules where dead cells with no living neighbors
+    /// are born (B0) would fill the infinite board, so they are refused. So is anything
+    /// that isn't life-like (see [Rule::is_life_like])
+    pub fn set_rule(&mut self, rule: Rule) -> Result<(), &'static str> {
+        if rule.next_state(CellState::Dead, 0) == CellState::Alive {
+            return Err("B0 rules can't be run on an infinite board");
+        }
+        if !rule.is_life_like() {
+            return Err(
+                "sparse boards only run totalistic 2 state rules on the 8 nearest neighbors",
+            );
+        }
+        self.rule = rule;
+        Ok(())
+    }
+    pub fn get(&self, x: i64, y: i64) -> CellState {
+        if self.alive.contains(&(x, y)) {
+            CellState::Alive
+        } else {
+            CellState::Dead
+        }
+    }
+    pub fn set(&mut self, x: i64, y: i64, cell: CellState) {
+        match cell {
+            CellState::Alive => self.alive.insert((x, y)),
+            CellState::Dead | CellState::Dying(_) => self.alive.remove(&(x, y)),
+        };
+    }
+    /// Works the same as [Game::set_many], 'cells' is either len 1 or the same length as 'pos'.
+    ///
+    /// If cells.len() != 1 or pos.len(), the function will panic!
+    pub fn set_many(&mut self, pos: &[(i64, i64)], cells: &[CellState]) {
+        if cells.len() != 1 {
+            assert_eq!(pos.len(), cells.len());
+            for ((x, y), c) in pos.iter().zip(cells) {
+                self.set(*x, *y, *c);
+            }
+            return;
+        }
+        for (x, y) in pos.iter() {
+            self.set(*x, *y, cells[0]);
+        }
+    }
+    pub fn clear(&mut self) {
+        self.alive.clear();
+    }
+    pub fn population(&self) -> usize {
+        self.alive.len()
+    }
+    pub fn live_cells(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.alive.iter().copied()
+    }
+    /// The smallest rectangle holding every living cell, as ((min x, min y), (max x, max y))
+    /// (both inclusive). None if nothing is alive
+    pub fn bounding_box(&self) -> Option<((i64, i64), (i64, i64))> {
+        let mut cells = self.alive.iter();
+        let first = *cells.next()?;
+        Some(cells.fold((first, first), |((x0, y0), (x1, y1)), (x, y)| {
+            ((x0.min(*x), y0.min(*y)), (x1.max(*x), y1.max(*y)))
+        }))
+    }
+    fn iterate(&mut self) {
+        let mut counts: HashMap<(i64, i64), usize> = HashMap::with_capacity(self.alive.len() * 8);
+        for (x, y) in self.alive.iter() {
+            // Living cells are checked even w/o living neighbors, for S0 rules
+            counts.entry((*x, *y)).or_insert(0);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        *counts.entry((x + dx, y + dy)).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+        // Only living cells & ones w/ a living neighbor can be alive next generation
+        // (B0 isn't allowed)
+        self.alive = counts
+            .into_iter()
+            .filter(|(pos, count)| {
+                let cell = self.get(pos.0, pos.1);
+                self.rule.next_state(cell, *count) == CellState::Alive
+            })
+            .map(|(pos, _)| pos)
+            .collect();
+        self.generation += 1;
+    }
+    pub fn step(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.iterate();
+        }
+    }
+    /// How many generations the board has been stepped
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+    /// The rows of the viewport with its top left corner at 'origin'
+    pub fn rows(
+        &self,
+        origin: (i64, i64),
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = Vec<CellState>> + '_ {
+        (0..height as i64).map(move |y| {
+            (0..width as i64)
+                .map(|x| self.get(origin.0 + x, origin.1 + y))
+                .collect()
+        })
+    }
+    /// Copies a viewport of the board into a [Game], with 'origin' becoming (0, 0)
+    pub fn to_game(&self, origin: (i64, i64), width: usize, height: usize) -> Game {
+        let mut game = Game::new(width, height);
+        game.set_rule(self.rule);
+        let cells = self.rows(origin, width, height).flatten().collect();
+        game.replace_buffer(cells).unwrap();
+        game
+    }
+}
@@ -0,0 +1,52 @@
+use crate::game::CellState;
+
+/// Numbers about a board & its last generation, see [crate::game::Game::stats]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub generation: u64,
+    /// How many cells are alive (dying cells don't count)
+    pub population: usize,
+    /// How many cells came alive in the last generation
+    pub births: usize,
+    /// How many living cells died (or started dying) in the last generation
+    pub deaths: usize,
+    /// The smallest rectangle holding every living cell, as ((min x, min y), (max x, max y))
+    /// (both inclusive). None if nothing is alive
+    pub bounding_box: Option<((usize, usize), (usize, usize))>,
+}
+impl Stats {
+    /// Measures the population & bounding box of a board stored row by row,
+    /// births & deaths are left at 0
+    pub fn measure(cells: &[CellState], width: usize, generation: u64) -> Self {
+        let mut stats = Stats {
+            generation,
+            ..Stats::default()
+        };
+        for (index, _) in cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == CellState::Alive)
+        {
+            let (x, y) = (index % width, index / width);
+            stats.population += 1;
+            stats.bounding_box = Some(match stats.bounding_box {
+                None => ((x, y), (x, y)),
+                Some(((x0, y0), (x1, y1))) => ((x0.min(x), y0.min(y)), (x1.max(x), y1.max(y))),
+            });
+        }
+        stats
+    }
+}
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Generation: {}, Population: {} (+{} -{})",
+            self.generation, self.population, self.births, self.deaths
+        )?;
+        if let Some(((x0, y0), (x1, y1))) = self.bounding_box {
+            write!(f, ", Live area: ({x0}, {y0}) to ({x1}, {y1})")?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,14 @@
 #[cfg(test)]
+use crate::apgcode::{Apgcode, ObjectKind};
+#[cfg(test)]
+use crate::board::{Board, Viewport};
+#[cfg(test)]
+use crate::catalogue::{Catalogue, CatalogueCommand};
+#[cfg(test)]
+use crate::cycle::{board_hash, cell_hash, Cycle};
+#[cfg(test)]
+use crate::diff::Diff;
+#[cfg(test)]
 use crate::game;
 #[cfg(test)]
 use std::io::Write;
@@ -6,8 +16,28 @@ use std::io::Write;
 #[cfg(test)]
 use crate::game::CellState;
 #[cfg(test)]
+use crate::hashlife::HashLife;
+#[cfg(test)]
+use crate::packed::PackedGame;
+#[cfg(test)]
+use crate::pattern::{PasteMode, Rect, Transform};
+#[cfg(test)]
+use crate::resize::{Anchor, Resize};
+#[cfg(test)]
+use crate::rule::{Rule, RuleError};
+#[cfg(test)]
 use crate::save_load;
 #[cfg(test)]
+use crate::soup::{Soup, Symmetry};
+#[cfg(test)]
+use crate::sparse::SparseGame;
+#[cfg(test)]
+use crate::stats::Stats;
+#[cfg(test)]
+use crate::topology::Topology;
+#[cfg(test)]
+use crate::verify::{Engine, Verify, ENGINES};
+#[cfg(test)]
 use crate::{GAME_X, GAME_Y};
 
 #[test]
@@ -130,3 +160,1469 @@ fn new_board_updating() {
         board.step(1);
     }
 }
+
+#[test]
+fn rule_parsing() {
+    assert_eq!(Rule::parse("B3/S23"), Ok(Rule::conway()));
+    assert_eq!(Rule::parse("s23/b3"), Ok(Rule::conway()));
+    assert_eq!(Rule::parse("23/3"), Ok(Rule::conway()));
+    for rulestring in ["B36/S23", "B2/S", "B3678/S34678"] {
+        assert_eq!(Rule::parse(rulestring).unwrap().to_string(), rulestring);
+    }
+
+    assert_eq!(Rule::parse(""), Err(RuleError::Empty));
+    assert_eq!(Rule::parse("B3"), Err(RuleError::WrongSectionCount(1)));
+    assert_eq!(
+        Rule::parse("B3/S2/C3/4"),
+        Err(RuleError::WrongSectionCount(4))
+    );
+    assert_eq!(Rule::parse("B3/X23"), Err(RuleError::UnknownSection('X')));
+    assert_eq!(Rule::parse("B3/B23"), Err(RuleError::RepeatedSection('B')));
+    assert_eq!(Rule::parse("B3/S2+"), Err(RuleError::InvalidCharacter('+')));
+    assert_eq!(Rule::parse("B9/S23"), Err(RuleError::CountOutOfRange(9)));
+}
+#[test]
+fn seeds_rule_stepping() {
+    // Under B2/S nothing survives, so a domino turns into two dominoes
+    let mut board = game::Game::new(6, 6);
+    board.set_rule(Rule::parse("B2/S").unwrap());
+    board.set_many(&[(2, 2), (3, 2)], &[CellState::Alive]);
+    board.step(1);
+
+    let mut expected = game::Game::new(6, 6);
+    expected.set_many(&[(2, 1), (3, 1), (2, 3), (3, 3)], &[CellState::Alive]);
+    assert_eq!(board, expected);
+}
+
+#[cfg(test)]
+const GLIDER: [(usize, usize); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+#[test]
+fn glider_wraps_on_torus() {
+    // A glider moves 1 cell diagonally every 4 generations, so it's back where it started
+    // after crossing the whole board
+    let mut board = game::Game::with_topology(10, 10, Topology::Torus);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    let start = board.clone();
+
+    board.step(40);
+    assert_eq!(board, start);
+}
+#[test]
+fn glider_dies_on_bounded_edge() {
+    let mut board = game::Game::with_topology(10, 10, Topology::Bounded);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    board.step(40);
+
+    // It ends up as a block in the bottom right corner
+    let mut block = game::Game::new(10, 10);
+    block.set_many(&[(8, 8), (9, 8), (8, 9), (9, 9)], &[CellState::Alive]);
+    assert_eq!(board, block);
+}
+#[test]
+fn topology_resolves_edges() {
+    assert_eq!(Topology::Bounded.resolve(-1, 0, 5, 4), None);
+    assert_eq!(Topology::AliveBorder.resolve(0, 4, 5, 4), None);
+    assert_eq!(Topology::Torus.resolve(-1, 4, 5, 4), Some((4, 0)));
+    assert_eq!(Topology::KleinBottle.resolve(1, -1, 5, 4), Some((3, 3)));
+    assert_eq!(Topology::KleinBottle.resolve(5, 1, 5, 4), Some((0, 1)));
+    assert_eq!(Topology::CrossSurface.resolve(5, 1, 5, 4), Some((0, 2)));
+    assert_eq!(Topology::CrossSurface.resolve(1, 4, 5, 4), Some((3, 0)));
+
+    // The old & new boards should agree on the edges too
+    for topology in Topology::ALL {
+        let mut old = game::GameBoardOld::new(6, 6);
+        old.topology = topology;
+        old.set_cells(
+            vec![(0, 0), (1, 0), (5, 0), (0, 5), (3, 5)],
+            CellState::Alive,
+        );
+        let mut new = game::Game::with_topology(6, 6, topology);
+        new.clone_from_old(&old).unwrap();
+
+        let old = game::run_iterations(&old, 3);
+        new.step(3);
+        let mut expected = game::Game::new(6, 6);
+        expected.clone_from_old(&old).unwrap();
+        assert_eq!(new, expected, "{topology} boards disagree");
+    }
+}
+#[test]
+fn topology_is_saved() {
+    let mut board = game::Game::with_topology(8, 8, Topology::KleinBottle);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    save_load::save_game(&board, "test-topology.txt").unwrap();
+    let loaded = save_load::load_game("test-topology.txt").unwrap();
+    std::fs::remove_file("test-topology.txt").unwrap();
+
+    assert_eq!(loaded.topology(), Topology::KleinBottle);
+    assert_eq!(loaded, board);
+}
+#[test]
+fn sparse_glider_never_hits_an_edge() {
+    let mut board = SparseGame::new();
+    let glider: Vec<(i64, i64)> = GLIDER.iter().map(|(x, y)| (*x as i64, *y as i64)).collect();
+    board.set_many(&glider, &[CellState::Alive]);
+
+    board.step(100_000);
+    assert_eq!(board.population(), 5);
+    assert_eq!(
+        board.bounding_box(),
+        Some(((25_000, 25_000), (25_002, 25_002)))
+    );
+
+    // It should also work going the other way, into negative coordinates
+    let mut board = SparseGame::new();
+    let flipped: Vec<(i64, i64)> = glider.iter().map(|(x, y)| (-x, -y)).collect();
+    board.set_many(&flipped, &[CellState::Alive]);
+    board.step(400);
+    assert_eq!(board.bounding_box(), Some(((-102, -102), (-100, -100))));
+}
+#[test]
+fn sparse_matches_game() {
+    let mut game = game::Game::new(20, 20);
+    // R-pentomino, it stays inside the board for the first few generations
+    game.set_many(
+        &[(10, 9), (11, 9), (9, 10), (10, 10), (10, 11)],
+        &[CellState::Alive],
+    );
+    let mut sparse = SparseGame::from_game(&game, (-10, -10));
+
+    for _ in 0..10 {
+        game.step(1);
+        sparse.step(1);
+        assert_eq!(sparse.to_game((-10, -10), 20, 20), game);
+    }
+    assert!(sparse.set_rule(Rule::parse("B0/S8").unwrap()).is_err());
+}
+#[test]
+fn hashlife_matches_game() {
+    let mut game = game::Game::new(200, 200);
+    let r_pentomino = [(100, 99), (101, 99), (99, 100), (100, 100), (100, 101)];
+    game.set_many(&r_pentomino, &[CellState::Alive]);
+    let mut life = HashLife::from_game(&game).unwrap();
+
+    game.step(100);
+    life.step(100);
+    assert_eq!(life.generation(), 100);
+    assert_eq!(life.to_game((0, 0), 200, 200), game);
+    assert_eq!(life.population(), 121);
+}
+#[test]
+fn hashlife_big_jumps() {
+    let mut life = HashLife::new(Rule::conway()).unwrap();
+    for (x, y) in GLIDER {
+        life.set(x as i64, y as i64, CellState::Alive);
+    }
+    life.step_pow2(40);
+
+    let moved = 1i64 << 38;
+    assert_eq!(life.population(), 5);
+    assert_eq!(
+        life.bounding_box(),
+        Some(((moved, moved), (moved + 2, moved + 2)))
+    );
+    assert_eq!(life.get(moved + 1, moved), CellState::Alive);
+    assert_eq!(life.get(moved, moved), CellState::Dead);
+}
+
+/// A cheap & repeatable scattering of living cells, about 1/3 alive
+#[cfg(test)]
+fn pseudo_random_cells(len: usize, mut seed: u64) -> Vec<CellState> {
+    (0..len)
+        .map(|_| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            if (seed >> 33).is_multiple_of(3) {
+                CellState::Alive
+            } else {
+                CellState::Dead
+            }
+        })
+        .collect()
+}
+#[test]
+fn packed_matches_game() {
+    let rules = ["B3/S23", "B36/S23", "B3678/S34678", "B2/S", "B0/S8"];
+    let topologies = [Topology::Bounded, Topology::Torus, Topology::AliveBorder];
+    // 130 wide, so rows take 3 words and the last one is partly used
+    for (i, rule) in rules.iter().enumerate() {
+        for topology in topologies {
+            let mut game = game::Game::with_topology(130, 70, topology);
+            game.set_rule(Rule::parse(rule).unwrap());
+            game.replace_buffer(pseudo_random_cells(130 * 70, i as u64))
+                .unwrap();
+            let mut packed = PackedGame::from_game(&game).unwrap();
+            assert_eq!(packed.to_game(), game);
+
+            for generation in 1..=8 {
+                game.step(1);
+                packed.step(1);
+                assert_eq!(
+                    packed.to_game(),
+                    game,
+                    "{rule} on {topology} differs at generation {generation}"
+                );
+            }
+        }
+    }
+    let klein = game::Game::with_topology(10, 10, Topology::KleinBottle);
+    assert!(PackedGame::from_game(&klein).is_err());
+}
+#[test]
+fn threaded_stepping_matches() {
+    for topology in Topology::ALL {
+        let mut single = game::Game::with_topology(97, 61, topology);
+        single.set_rule(Rule::parse("B36/S23").unwrap());
+        single
+            .replace_buffer(pseudo_random_cells(97 * 61, 7))
+            .unwrap();
+
+        for threads in [2, 3, 7, 16, 100] {
+            let mut threaded = single.clone();
+            threaded.set_threads(threads);
+            let mut expected = single.clone();
+            for _ in 0..10 {
+                expected.step(1);
+                threaded.step(1);
+                assert_eq!(threaded, expected, "{threads} threads on {topology}");
+            }
+        }
+    }
+}
+#[test]
+fn generations_rules() {
+    let brians_brain = Rule::parse("/2/3").unwrap();
+    assert_eq!(brians_brain, Rule::parse("B2/S/C3").unwrap());
+    assert_eq!(brians_brain.to_string(), "B2/S/C3");
+    assert_eq!(Rule::parse("345/2/4").unwrap().to_string(), "B2/S345/C4");
+    assert_eq!(
+        Rule::parse("B2/S/C1"),
+        Err(RuleError::StateCountOutOfRange(1))
+    );
+    assert_eq!(Rule::parse("B2/S/Cx"), Err(RuleError::InvalidStateCount));
+
+    // A living cell that doesn't survive takes (states - 2) generations to die
+    let star_wars = Rule::parse("345/2/4").unwrap();
+    assert_eq!(
+        star_wars.next_state(CellState::Alive, 0),
+        CellState::Dying(2)
+    );
+    assert_eq!(
+        star_wars.next_state(CellState::Dying(2), 2),
+        CellState::Dying(1)
+    );
+    assert_eq!(
+        star_wars.next_state(CellState::Dying(1), 2),
+        CellState::Dead
+    );
+    assert_eq!(star_wars.next_state(CellState::Dead, 2), CellState::Alive);
+    for index in 0..4 {
+        let cell = CellState::from_state_index(index, 4);
+        assert_eq!(cell.state_index(4), index);
+    }
+}
+#[test]
+fn brians_brain_stepping() {
+    // In Brian's Brain every living cell starts dying right away
+    let mut board = game::Game::new(6, 6);
+    board.set_rule(Rule::parse("B2/S/C3").unwrap());
+    board.set_many(&[(2, 2), (3, 2)], &[CellState::Alive]);
+    board.step(1);
+
+    let mut expected = game::Game::new(6, 6);
+    expected.set_many(&[(2, 2), (3, 2)], &[CellState::Dying(1)]);
+    expected.set_many(&[(2, 1), (3, 1), (2, 3), (3, 3)], &[CellState::Alive]);
+    assert_eq!(board, expected);
+
+    // Dying cells can be saved & loaded w/ the rule
+    save_load::save_game(&board, "test-generations.txt").unwrap();
+    let loaded = save_load::load_game("test-generations.txt").unwrap();
+    std::fs::remove_file("test-generations.txt").unwrap();
+    assert_eq!(loaded, board);
+    assert_eq!(loaded.rule(), board.rule());
+}
+
+#[test]
+fn neighborhood_rules() {
+    let bosco = Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+    assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+    assert!(!bosco.is_life_like());
+    assert_eq!(Rule::parse("B2/S34H").unwrap().to_string(), "B2/S34H");
+    assert_eq!(Rule::parse("B13/S012V").unwrap().to_string(), "B13/S012V");
+    assert_eq!(Rule::parse("B5/SV"), Err(RuleError::CountOutOfRange(5)));
+    assert_eq!(Rule::parse("B7/S34H"), Err(RuleError::CountOutOfRange(7)));
+    assert_eq!(
+        Rule::parse("R9,C0,M0,S2..3,B3,NM"),
+        Err(RuleError::RadiusOutOfRange(9))
+    );
+    assert_eq!(
+        Rule::parse("R2,C0,M0,S2..3,B3,NX"),
+        Err(RuleError::UnknownNeighborhood('X'))
+    );
+
+    // Counting the cell itself (M1), survival on 3-4 is the same as S23
+    let life_with_center = Rule::parse("R1,C0,M1,S3..4,B3,NM").unwrap();
+    let mut board = game::Game::new(20, 12);
+    board
+        .replace_buffer(pseudo_random_cells(20 * 12, 11))
+        .unwrap();
+    let mut expected = board.clone();
+    board.set_rule(life_with_center);
+    board.step(10);
+    expected.step(10);
+    assert_eq!(board, expected);
+
+    // Only life-like rules fit in the other engines
+    assert!(HashLife::new(bosco).is_err());
+    assert!(PackedGame::new(4, 4).set_rule(bosco).is_err());
+    assert!(SparseGame::new()
+        .set_rule(Rule::parse("B2/S34H").unwrap())
+        .is_err());
+}
+
+#[test]
+fn von_neumann_stepping() {
+    let mut board = game::Game::new(7, 7);
+    board.set_rule(Rule::parse("B1/SV").unwrap());
+    board.set(3, 3, CellState::Alive);
+    board.step(1);
+
+    let mut expected = game::Game::new(7, 7);
+    expected.set_many(&[(3, 2), (2, 3), (4, 3), (3, 4)], &[CellState::Alive]);
+    assert_eq!(board, expected);
+
+    // The neighborhood is part of the saved rule
+    save_load::save_game(&board, "test-neighborhood.txt").unwrap();
+    let loaded = save_load::load_game("test-neighborhood.txt").unwrap();
+    std::fs::remove_file("test-neighborhood.txt").unwrap();
+    assert_eq!(loaded.rule(), board.rule());
+}
+
+#[test]
+fn isotropic_rules() {
+    for rulestring in ["B2-a/S12", "B3/S2-i34q", "B2ce3-ak/S1e2/C4"] {
+        let rule = Rule::parse(rulestring).unwrap();
+        assert!(rule.is_isotropic());
+        assert_eq!(rule.to_string(), rulestring);
+    }
+    // Every letter of a count together is just the count
+    for (n, letters) in ["", "ce", "aceikn", "aceijknqry", "aceijknqrtwyz"]
+        .iter()
+        .enumerate()
+    {
+        let rule = Rule::parse(&format!("B{n}{letters}/S{}{letters}", 8 - n)).unwrap();
+        assert_eq!(rule, Rule::parse(&format!("B{n}/S{}", 8 - n)).unwrap());
+        assert!(!rule.is_isotropic());
+    }
+    assert_eq!(Rule::parse("B1a/S"), Err(RuleError::InvalidLetter(1, 'a')));
+    assert_eq!(Rule::parse("B2-/S"), Err(RuleError::InvalidCharacter('-')));
+    assert!(HashLife::new(Rule::parse("B2-a/S12").unwrap()).is_err());
+
+    // Only the middle cell has 2 neighbors opposite each other (2i), the ones
+    // beside it have 2 corners (2c)
+    let mut board = game::Game::new(5, 5);
+    board.set_rule(Rule::parse("B2i/S").unwrap());
+    board.set_many(&[(2, 1), (2, 3)], &[CellState::Alive]);
+    let mut old_board = game::GameBoardOld::new(5, 5);
+    old_board.rule = board.rule();
+    old_board.set(2, 1, CellState::Alive);
+    old_board.set(2, 3, CellState::Alive);
+    board.step(1);
+    let old_board = game::run_iterations(&old_board, 1);
+
+    let mut expected = game::Game::new(5, 5);
+    expected.set(2, 2, CellState::Alive);
+    assert_eq!(board, expected);
+    assert!(old_board
+        .space
+        .iter()
+        .flatten()
+        .zip(board.rows().flatten())
+        .all(|(a, b)| a == b));
+
+    save_load::save_game(&board, "test-isotropic.txt").unwrap();
+    let loaded = save_load::load_game("test-isotropic.txt").unwrap();
+    std::fs::remove_file("test-isotropic.txt").unwrap();
+    assert_eq!(loaded.rule(), board.rule());
+
+    // A domino's neighbors are all 2a, which B2-a leaves out
+    board.clear();
+    board.set_rule(Rule::parse("B2-a/S").unwrap());
+    board.set_many(&[(2, 2), (3, 2)], &[CellState::Alive]);
+    board.step(1);
+    assert_eq!(board, game::Game::new(5, 5));
+}
+
+#[test]
+fn undo_redo() {
+    let mut board = game::Game::new(10, 10);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    let start = board.clone();
+    board.step(3);
+    let stepped = board.clone();
+
+    // Each generation is undone one at a time, then the edit itself
+    for _ in 0..3 {
+        assert!(board.undo());
+    }
+    assert_eq!(board, start);
+    assert!(board.undo());
+    assert_eq!(board, game::Game::new(10, 10));
+    // Nothing being undone isn't an edit, so the GUI doesn't redraw
+    let edits = board.edits();
+    assert!(!board.undo());
+    assert_eq!(board.edits(), edits);
+    for _ in 0..4 {
+        assert!(board.redo());
+    }
+    assert_eq!(board, stepped);
+    let edits = board.edits();
+    assert!(!board.redo());
+    assert_eq!(board.edits(), edits);
+
+    // Most of the board changing is stored whole
+    board.fill();
+    assert!(board.undo());
+    assert_eq!(board, stepped);
+
+    // A new edit can't be followed by the old redos
+    board.set(0, 0, CellState::Alive);
+    assert!(!board.redo());
+
+    board.set_history_limit(2);
+    board.step(5);
+    assert!(board.undo() && board.undo());
+    assert!(!board.undo());
+}
+
+#[test]
+fn cycle_detection() {
+    let mut board = game::Game::new(8, 8);
+    board.set_many(&[(2, 3), (3, 3), (4, 3)], &[CellState::Alive]);
+    board.step(1);
+    assert_eq!(board.cycle(), None);
+    board.step(1);
+    assert_eq!(
+        board.cycle(),
+        Some(Cycle {
+            start: 0,
+            period: 2
+        })
+    );
+
+    // Edits start over (at generation 2), 3 cells of a block turn into the block
+    board.clear();
+    board.set_many(&[(2, 2), (3, 2), (2, 3)], &[CellState::Alive]);
+    board.step(3);
+    assert_eq!(
+        board.cycle(),
+        Some(Cycle {
+            start: 3,
+            period: 1
+        })
+    );
+
+    // A glider on a torus gets back to where it started after moving all the way around
+    let mut board = game::Game::with_topology(8, 8, Topology::Torus);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    board.step(40);
+    assert_eq!(
+        board.cycle(),
+        Some(Cycle {
+            start: 0,
+            period: 32
+        })
+    );
+
+    // Hashes kept up to date from the changed cells match hashing the whole board
+    let before = board.cells().to_vec();
+    board.step(1);
+    let changed = board.last_diff().unwrap();
+    let hash = changed.iter().fold(board_hash(&before), |hash, (i, b, a)| {
+        hash ^ cell_hash(*i, *b) ^ cell_hash(*i, *a)
+    });
+    assert_eq!(hash, board_hash(board.cells()));
+    assert_ne!(hash, board_hash(&before));
+    assert_eq!(board_hash(&[CellState::Dead; 4]), 0);
+    // Edits are noticed by a change in the count, steps by the generation
+    let edits = board.edits();
+    board.step(1);
+    assert_eq!(board.edits(), edits);
+    board.clear();
+    assert_ne!(board.edits(), edits);
+}
+
+#[test]
+fn board_stats() {
+    let mut board = game::Game::new(8, 8);
+    board.set_many(&[(2, 3), (3, 3), (4, 3)], &[CellState::Alive]);
+    board.step(1);
+    assert_eq!(
+        board.stats(),
+        Stats {
+            generation: 1,
+            population: 3,
+            births: 2,
+            deaths: 2,
+            bounding_box: Some(((3, 2), (3, 4))),
+        }
+    );
+
+    // Generations where nothing changes are still counted, and can be undone
+    board.clear();
+    board.set_many(&[(0, 0), (1, 0), (0, 1), (1, 1)], &[CellState::Alive]);
+    board.step(2);
+    assert_eq!(board.generation(), 3);
+    assert_eq!((board.stats().births, board.stats().deaths), (0, 0));
+    assert!(board.undo() && board.undo());
+    assert_eq!(board.generation(), 1);
+    assert_eq!(board.population(), 4);
+
+    board.clear();
+    assert_eq!(board.stats().bounding_box, None);
+}
+
+#[test]
+fn active_tiles() {
+    let rules = [
+        "B3/S23",
+        "B36/S23",
+        "B2/S/C3",
+        "B2-a/S12",
+        "R2,C0,M1,S3..5,B3..4,NM",
+    ];
+    let topologies = [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+        Topology::AliveBorder,
+    ];
+    for (seed, rule) in rules.iter().enumerate() {
+        let rule = Rule::parse(rule).unwrap();
+        for topology in topologies {
+            // A small soup in a corner of the board, so most tiles stay quiet
+            let mut tracked = game::Game::new(50, 40);
+            tracked.set_rule(rule);
+            tracked.set_topology(topology);
+            let mut cells = vec![CellState::Dead; 50 * 40];
+            let soup = pseudo_random_cells(12 * 12, seed as u64);
+            for (i, cell) in soup.into_iter().enumerate() {
+                cells[(i / 12) * 50 + i % 12] = cell;
+            }
+            tracked.replace_buffer(cells).unwrap();
+            let mut full = tracked.clone();
+            assert!(tracked.changed_cells().is_none());
+
+            for _ in 0..40 {
+                let before = tracked.clone();
+                tracked.step(1);
+                // Setting the rule forgets the changes, so every cell is worked out
+                full.set_rule(rule);
+                full.step(1);
+                assert_eq!(tracked, full, "{rule} on {topology:?}");
+
+                let mut changed: Vec<(usize, usize)> = tracked.changed_cells().unwrap().collect();
+                changed.sort_by_key(|(x, y)| (*y, *x));
+                let expected: Vec<(usize, usize)> = (0..40)
+                    .flat_map(|y| (0..50).map(move |x| (x, y)))
+                    .filter(|(x, y)| {
+                        before.rows().nth(*y).unwrap()[*x] != tracked.rows().nth(*y).unwrap()[*x]
+                    })
+                    .collect();
+                assert_eq!(changed, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn resizing() {
+    let mut board = game::Game::new(10, 10);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    let glider = board.stats().bounding_box.unwrap();
+
+    // Cropping keeps just the glider, padding moves it away from the edges
+    board.crop_to_live().unwrap();
+    assert_eq!((board.x_max, board.y_max), (3, 3));
+    assert_eq!(board.stats().population, 5);
+    board.pad(2).unwrap();
+    assert_eq!((board.x_max, board.y_max), (7, 7));
+    board.resize(10, 10, Anchor::TopLeft).unwrap();
+    let mut expected = game::Game::new(10, 10);
+    let moved: Vec<(usize, usize)> = GLIDER.iter().map(|(x, y)| (x + 2, y + 2)).collect();
+    expected.set_many(&moved, &[CellState::Alive]);
+    assert_eq!(board, expected);
+
+    // Growing around the center & shrinking back gives the same board
+    board.resize(20, 30, Anchor::Center).unwrap();
+    assert_eq!(board.stats().population, 5);
+    board.resize(10, 10, Anchor::Center).unwrap();
+    assert_eq!(board, expected);
+
+    // Anchoring to the bottom right moves everything by the growth
+    board.resize(15, 12, Anchor::BottomRight).unwrap();
+    let ((x, y), _) = board.stats().bounding_box.unwrap();
+    assert_eq!((x, y), (glider.0 .0 + 2 + 5, glider.0 .1 + 2 + 2));
+
+    // The history of the old size can't be undone
+    assert!(!board.undo());
+    // Non-square boards still step
+    board.step(4);
+    assert_eq!(board.stats().population, 5);
+
+    board.clear();
+    assert!(board.crop_to_live().is_err());
+    assert!(board.resize(0, 5, Anchor::TopLeft).is_err());
+    assert!(Resize::Pad(1).apply(&[], (0, 5)).is_err());
+    assert_eq!("bl".parse(), Ok(Anchor::BottomLeft));
+    assert_eq!("Top Right".parse(), Ok(Anchor::TopRight));
+}
+
+#[test]
+fn clipboard() {
+    let mut board = game::Game::new(10, 10);
+    board.set_many(&GLIDER, &[CellState::Alive]);
+    let glider = board.copy(Rect::from_corners((2, 2), (0, 0))).unwrap();
+    assert_eq!((glider.width(), glider.height()), (3, 3));
+
+    // 4 quarter turns (or 2 flips) get back to the start
+    let mut turned = glider.clone();
+    for _ in 0..4 {
+        turned = turned.transformed(Transform::Rotate90);
+    }
+    assert_eq!(turned, glider);
+    assert_eq!(
+        glider
+            .transformed(Transform::Rotate90)
+            .transformed(Transform::Rotate270),
+        glider
+    );
+    assert_eq!(
+        glider
+            .transformed(Transform::FlipHorizontal)
+            .transformed(Transform::FlipVertical),
+        glider.transformed(Transform::Rotate180)
+    );
+    // The glider's tail (top middle) ends up on the right when turned clockwise
+    let turned = glider.transformed(Transform::Rotate90);
+    assert_eq!(turned.get(2, 1), CellState::Alive);
+    assert_eq!(turned.get(1, 0), CellState::Dead);
+
+    // Cutting & pasting elsewhere moves the glider
+    let cut = board.cut(Rect::from_corners((0, 0), (2, 2))).unwrap();
+    assert_eq!(board.population(), 0);
+    board.paste(&cut, (5, 5), PasteMode::Or);
+    let mut expected = game::Game::new(10, 10);
+    let moved: Vec<(usize, usize)> = GLIDER.iter().map(|(x, y)| (x + 5, y + 5)).collect();
+    expected.set_many(&moved, &[CellState::Alive]);
+    assert_eq!(board, expected);
+
+    // Xor'ing the same pattern removes it, overwriting w/ dead cells clears the area
+    board.paste(&cut, (5, 5), PasteMode::Xor);
+    assert_eq!(board.population(), 0);
+    board.fill();
+    board.paste(&cut, (5, 5), PasteMode::Overwrite);
+    assert_eq!(board.population(), 100 - 4);
+    board.paste(&cut, (5, 5), PasteMode::Or);
+    assert_eq!(board.population(), 100 - 4);
+
+    // Pasting past the edges leaves those cells off, & can be undone
+    board.clear();
+    board.paste(&cut, (8, 8), PasteMode::Or);
+    assert_eq!(board.population(), 1);
+    assert!(board.undo());
+    assert_eq!(board.population(), 0);
+    assert!(board.copy(Rect::from_corners((10, 0), (12, 2))).is_err());
+}
+
+#[test]
+fn pattern_catalogue() {
+    let catalogue = Catalogue::default();
+    let command: CatalogueCommand = "place Glider 10,10 rot90".parse().unwrap();
+    assert_eq!(
+        command,
+        CatalogueCommand::Place {
+            name: "Glider".to_string(),
+            at: Some((10, 10)),
+            orientation: vec![Transform::Rotate90],
+        }
+    );
+    assert!("place glider 10,x".parse::<CatalogueCommand>().is_err());
+    assert!("place glider 1,1 rot45"
+        .parse::<CatalogueCommand>()
+        .is_err());
+    assert!(catalogue.oriented("not-a-pattern", &[]).is_err());
+    assert!(catalogue.to_string().contains("gosper-gun"));
+
+    // Places a pattern on an empty board & steps it
+    let stepped = |name: &str, size: usize, at: (usize, usize), steps: usize| {
+        let mut board = game::Game::new(size, size);
+        board.paste(&catalogue.oriented(name, &[]).unwrap(), at, PasteMode::Or);
+        let start = board.stats();
+        board.step(steps);
+        (start, board)
+    };
+    // Spaceships come back to the same shape, moved
+    for (name, steps, moved) in [
+        ("glider", 4, (1, 1)),
+        ("lwss", 4, (-2, 0)),
+        ("hwss", 4, (-2, 0)),
+    ] {
+        let (start, board) = stepped(name, 20, (8, 8), steps);
+        let ((x0, y0), _) = start.bounding_box.unwrap();
+        let ((x1, y1), _) = board.stats().bounding_box.unwrap();
+        assert_eq!(board.stats().population, start.population, "{name}");
+        assert_eq!(
+            (x1 as isize - x0 as isize, y1 as isize - y0 as isize),
+            moved,
+            "{name}"
+        );
+    }
+    // Oscillators repeat
+    for (name, period) in [("blinker", 2u64), ("pulsar", 3), ("pentadecathlon", 15)] {
+        let (_, board) = stepped(name, 21, (4, 4), period as usize + 1);
+        assert_eq!(board.cycle(), Some(Cycle { start: 0, period }), "{name}");
+    }
+    // The gun adds a glider every 30 generations, & diehard dies
+    let (start, board) = stepped("gosper-gun", 50, (2, 2), 30);
+    assert_eq!(board.population(), start.population + 5);
+    let (_, board) = stepped("diehard", 80, (36, 36), 130);
+    assert_eq!(board.population(), 0);
+
+    // Patterns can be turned, & added from save files
+    let glider = catalogue.oriented("glider", &[]).unwrap();
+    let turned = catalogue
+        .oriented("glider", &[Transform::Rotate90, Transform::FlipVertical])
+        .unwrap();
+    assert_eq!(
+        turned,
+        glider
+            .transformed(Transform::Rotate90)
+            .transformed(Transform::FlipVertical)
+    );
+    let mut board = game::Game::new(12, 12);
+    board.paste(&turned, (4, 5), PasteMode::Or);
+    save_load::save_game(&board, "test-catalogue.txt").unwrap();
+    let mut catalogue = catalogue;
+    let loaded = save_load::load_pattern("test-catalogue.txt").unwrap();
+    std::fs::remove_file("test-catalogue.txt").unwrap();
+    catalogue.add("my-glider", "A turned glider", loaded);
+    assert_eq!(catalogue.get("MY-GLIDER").unwrap().pattern, turned);
+}
+
+#[test]
+fn random_soups() {
+    let region = Rect::from_corners((2, 3), (13, 14));
+    let soup = |seed, symmetry| {
+        let mut board = game::Game::new(20, 20);
+        board.randomize(region, 0.5, seed, symmetry).unwrap();
+        board
+    };
+    // The same seed gives the same soup, only inside the region
+    assert_eq!(soup(42, Symmetry::C1), soup(42, Symmetry::C1));
+    assert_ne!(soup(42, Symmetry::C1), soup(43, Symmetry::C1));
+    let board = soup(42, Symmetry::C1);
+    let ((x0, y0), (x1, y1)) = board.stats().bounding_box.unwrap();
+    assert!(x0 >= 2 && y0 >= 3 && x1 <= 13 && y1 <= 14);
+    assert!((40..=104).contains(&board.population()));
+
+    // Symmetric soups look the same turned or mirrored
+    for (symmetry, transforms) in [
+        (Symmetry::C2, vec![Transform::Rotate180]),
+        (Symmetry::C4, vec![Transform::Rotate90]),
+        (Symmetry::D2, vec![Transform::FlipHorizontal]),
+        (
+            Symmetry::D4,
+            vec![Transform::FlipHorizontal, Transform::FlipVertical],
+        ),
+        (
+            Symmetry::D8,
+            vec![Transform::Rotate90, Transform::FlipVertical],
+        ),
+    ] {
+        let pattern = soup(7, symmetry).copy(region).unwrap();
+        assert!(pattern != soup(7, Symmetry::C1).copy(region).unwrap());
+        for transform in transforms {
+            assert_eq!(pattern.transformed(transform), pattern, "{symmetry}");
+        }
+    }
+
+    // Soups can be written down & made again
+    let settings: Soup = "seed=99 density=0.3 symmetry=D4 region=0,0,9,5"
+        .parse()
+        .unwrap();
+    let again: Soup = settings.to_string().parse().unwrap();
+    assert_eq!(settings, again);
+    let (mut a, mut b) = (game::Game::new(12, 12), game::Game::new(12, 12));
+    a.apply_soup(&settings).unwrap();
+    b.randomize(Rect::from_corners((0, 0), (9, 5)), 0.3, 99, Symmetry::D4)
+        .unwrap();
+    assert_eq!(a, b);
+
+    let mut board = game::Game::new(20, 20);
+    assert!(board.randomize(region, 1.5, 1, Symmetry::C1).is_err());
+    let not_square = Rect::from_corners((0, 0), (5, 9));
+    assert!(board.randomize(not_square, 0.5, 1, Symmetry::C4).is_err());
+    assert!(board.randomize(not_square, 0.5, 1, Symmetry::C2).is_ok());
+    assert!("seed=x".parse::<Soup>().is_err());
+
+    // A region partly off the board gets the same cells as it would on a bigger one,
+    // even a symmetric one
+    let whole = Rect::from_corners((0, 0), (19, 19));
+    let (mut big, mut small) = (game::Game::new(20, 20), game::Game::new(10, 10));
+    big.randomize(whole, 0.5, 7, Symmetry::C4).unwrap();
+    small.randomize(whole, 0.5, 7, Symmetry::C4).unwrap();
+    let corner = Rect::from_corners((0, 0), (9, 9));
+    assert_eq!(big.copy(corner).unwrap(), small.copy(corner).unwrap());
+}
+
+#[test]
+fn board_diffs() {
+    let mut before = game::Game::new(10, 10);
+    before.paste(
+        &Catalogue::builtin().get("glider").unwrap().pattern,
+        (2, 2),
+        PasteMode::Or,
+    );
+    let mut after = before.clone();
+    after.step(1);
+
+    // The diff between generations is the same as the one from the last step
+    let diff = before.diff(&after).unwrap();
+    assert_eq!(after.last_diff(), Some(diff.clone()));
+    assert_eq!(diff.len(), 4);
+    assert_eq!(before.last_diff(), None);
+
+    // Patching the first board catches it up, & undoing the patch goes back
+    let mut patched = before.clone();
+    patched.apply_diff(&diff).unwrap();
+    assert_eq!(patched.cells(), after.cells());
+    assert!(patched.undo());
+    assert_eq!(patched.cells(), before.cells());
+
+    let mut cells = before.cells().to_vec();
+    diff.apply(&mut cells).unwrap();
+    assert_eq!(cells, after.cells());
+    diff.revert(&mut cells).unwrap();
+    assert_eq!(cells, before.cells());
+    diff.inverse().revert(&mut cells).unwrap();
+    assert_eq!(cells, after.cells());
+
+    // Diffs can be written down as text or bytes
+    let text = diff.to_string();
+    assert!(text.starts_with("diff 100\n"));
+    assert_eq!(text.parse::<Diff>().unwrap(), diff);
+    assert_eq!(Diff::from_bytes(&diff.to_bytes()).unwrap(), diff);
+    let mut big = game::Game::new(200, 200);
+    big.set(199, 199, CellState::Dying(3));
+    let far = game::Game::new(200, 200).diff(&big).unwrap();
+    assert_eq!(Diff::from_bytes(&far.to_bytes()).unwrap(), far);
+
+    // Only boards of the same size can be diffed or patched
+    assert!(before.diff(&game::Game::new(10, 12)).is_err());
+    assert!(game::Game::new(5, 5).apply_diff(&diff).is_err());
+    assert!(diff.apply(&mut [CellState::Dead; 4]).is_err());
+    assert!("diff 4\n4 ✗✓".parse::<Diff>().is_err());
+    assert!("diff x".parse::<Diff>().is_err());
+    assert!("diff 4\n1 ✗".parse::<Diff>().is_err());
+    let bytes = diff.to_bytes();
+    assert!(Diff::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Diff::from_bytes(&[bytes.clone(), vec![0]].concat()).is_err());
+    // 2 cells, the second's gap taking the index past usize::MAX
+    let max_gap = [[0xff; 9].as_slice(), &[0x01, 0, 1]].concat();
+    let bad_gap = [
+        u64::MAX.to_le_bytes().as_slice(),
+        &2u64.to_le_bytes(),
+        &max_gap,
+        &[0x01, 0, 1],
+    ]
+    .concat();
+    assert!(Diff::from_bytes(&bad_gap).is_err());
+}
+
+#[test]
+fn board_trait() {
+    /// Puts a glider on any kind of board & runs it, the way the front ends do
+    fn run_glider(mut board: impl Board) -> (Vec<CellState>, Vec<(usize, usize)>) {
+        let glider = Catalogue::builtin().oriented("glider", &[]).unwrap();
+        let mut cells = board.cells();
+        glider.paste_onto(&mut cells, board.width(), (1, 1), PasteMode::Or);
+        board.replace_cells(&cells).unwrap();
+        assert_eq!(board.population(), 5);
+        board.step(4);
+        (board.cells(), board.live_cells())
+    }
+    let expected = run_glider(game::Game::new(12, 12));
+    assert_eq!(expected.1.len(), 5);
+    assert_eq!(run_glider(game::GameBoardOld::new(12, 12)), expected);
+    assert_eq!(run_glider(PackedGame::new(12, 12)), expected);
+    assert_eq!(
+        run_glider(Viewport::new(SparseGame::new(), (-3, 7), 12, 12)),
+        expected
+    );
+    let life = HashLife::new(Rule::default()).unwrap();
+    assert_eq!(run_glider(Viewport::new(life, (5, -2), 12, 12)), expected);
+
+    // Save files work w/ every kind of board
+    let mut packed = PackedGame::new(6, 4);
+    packed.set(1, 2, CellState::Alive);
+    packed.set_topology(Topology::Torus).unwrap();
+    save_load::save_game(&packed, "test-board-trait.txt").unwrap();
+    let loaded = save_load::load("test-board-trait.txt", PackedGame::new).unwrap();
+    assert_eq!(loaded, packed);
+    let loaded = save_load::load_game("test-board-trait.txt").unwrap();
+    assert_eq!(Board::cells(&loaded), packed.cells());
+    assert_eq!(loaded.topology(), Topology::Torus);
+    // A sparse board can't have edges
+    let sparse = |w, h| Viewport::new(SparseGame::new(), (0, 0), w, h);
+    assert!(save_load::load("test-board-trait.txt", sparse).is_err());
+    std::fs::remove_file("test-board-trait.txt").unwrap();
+
+    // Resizing an infinite board moves the viewport, so nothing is lost
+    let mut view = sparse(10, 10);
+    view.set(2, 3, CellState::Alive);
+    view.set(4, 8, CellState::Alive);
+    view.resize(Resize::CropToLive).unwrap();
+    assert_eq!((view.size(), view.origin), ((3, 6), (2, 3)));
+    view.resize(Resize::To(1, 1, Anchor::TopLeft)).unwrap();
+    view.resize(Resize::Pad(2)).unwrap();
+    assert_eq!((view.size(), view.origin), ((5, 5), (0, 1)));
+    // Only one cell is in view, but the other one is still there
+    assert_eq!((view.population(), view.engine.population()), (1, 2));
+    assert!(view.set_topology(Topology::Torus).is_err());
+    // It has no edges, so it can't be saved as a bounded board
+    assert_eq!(view.topology(), Topology::Unbounded);
+    assert_eq!(
+        save_load::save_game(&view, "test-unbounded.txt"),
+        Err(save_load::SaveLoadError::Unsupported)
+    );
+    let mut bounded = game::Game::new(5, 5);
+    assert!(Board::set_topology(&mut bounded, Topology::Unbounded).is_err());
+    assert_eq!(Board::get(&view, 4, 8), None);
+
+    // The GUI's editing works on any board, only some of them can undo it
+    fn edit(mut board: impl Board) -> (Vec<CellState>, bool) {
+        board.set_many(&GLIDER, &[CellState::Alive]);
+        let glider = board.cut(Rect::from_corners((0, 0), (2, 2))).unwrap();
+        board.paste(&glider, (6, 6), PasteMode::Or);
+        let soup: Soup = "seed=3 density=0.5 region=0,0,4,4".parse().unwrap();
+        board.apply_soup(&soup).unwrap();
+        assert_eq!(board.stats().population, board.population());
+        let cells = board.cells();
+        (cells, board.undo())
+    }
+    let (cells, undone) = edit(game::Game::new(10, 10));
+    assert!(undone);
+    assert_eq!(edit(PackedGame::new(10, 10)), (cells, false));
+
+    // The engine picked w/ "--engine" runs boxed, w/ whatever extras it has
+    for engine in ENGINES {
+        let mut board = (engine.new)(8, 8);
+        board.set_many(&GLIDER, &[CellState::Alive]);
+        board.step(4);
+        assert_eq!(board.population(), 5, "{}", engine.name);
+        let undone = board.undo();
+        assert_eq!(undone, engine.name == "game", "{}", engine.name);
+        assert_eq!(board.generation(), 4 - undone as u64, "{}", engine.name);
+    }
+    // Every engine counts its generations, even the ones w/o edges
+    let mut view = sparse(8, 8);
+    view.step(2);
+    assert_eq!(view.stats().generation, 2);
+    let mut view = Viewport::new(HashLife::new(Rule::default()).unwrap(), (0, 0), 8, 8);
+    view.step(3);
+    assert_eq!(view.stats().generation, 3);
+    let mut packed: Box<dyn Board> = Box::new(PackedGame::new(8, 8));
+    packed.step(3);
+    packed.resize(Resize::Pad(1)).unwrap();
+    assert_eq!(packed.stats().generation, 3);
+}
+
+#[test]
+fn engines_agree() {
+    // Rectangular boards, cell by cell
+    let mut board = game::Game::new(7, 3);
+    board.set(6, 0, CellState::Alive);
+    board.set_many(&[(0, 2), (3, 1)], &[CellState::Alive]);
+    board[(5, 2)] = CellState::Alive;
+    assert_eq!(board.get(6, 0), Some(CellState::Alive));
+    assert_eq!(board.get(0, 1), Some(CellState::Dead));
+    assert_eq!(board.get(7, 0), None);
+    assert_eq!(board[(3, 1)], CellState::Alive);
+    let rows: Vec<&[CellState]> = board.rows().collect();
+    assert_eq!(rows[0][6], CellState::Alive);
+    assert_eq!(rows[2][0], CellState::Alive);
+    assert_eq!(rows[2][5], CellState::Alive);
+    assert_eq!(board.population(), 4);
+    let mut tall = game::Game::new(3, 9);
+    tall.set(2, 8, CellState::Alive);
+    assert_eq!(Board::live_cells(&tall), vec![(2, 8)]);
+
+    // Every engine runs the same, on all sorts of sizes, topologies & rules
+    let rules = ["B3/S23", "B3/S023", "B2/S", "B2/S/C4"].map(|r| Rule::parse(r).unwrap());
+    let verify = Verify {
+        soups: 40,
+        generations: 12,
+        max_size: (24, 24),
+        seed: 20,
+        rules: rules.to_vec(),
+    };
+    assert_eq!(verify.run(&ENGINES), Ok(40));
+    // Only 1 engine can run a Generations rule, so there's nothing to compare
+    let generations = Verify {
+        rules: vec![rules[3]],
+        ..verify.clone()
+    };
+    assert_eq!(generations.run(&[ENGINES[0], ENGINES[2]]), Ok(0));
+
+    // A board that doesn't stop at the edge disagrees once something gets there
+    let wide = Engine {
+        name: "wide",
+        new: |w, h| Box::new(game::Game::new(w + 1, h)),
+    };
+    let verify = Verify {
+        soups: 20,
+        max_size: (10, 5),
+        ..verify
+    };
+    let divergence = verify.run(&[ENGINES[0], wide]).unwrap_err();
+    assert_eq!(divergence.engines, ("game", "wide"));
+    assert!(divergence.generation > 0);
+    let (x, y) = divergence.cell;
+    assert!(x == 0 || y == 0 || x + 1 == divergence.size.0 || y + 1 == divergence.size.1);
+    assert!(divergence.to_string().contains("disagree"));
+
+    // A lone cell survives w/ 0 neighbors under S0 rules, on every engine
+    let rule = Rule::parse("B3/S023").unwrap();
+    let life = HashLife::new(rule).unwrap();
+    let mut boards: Vec<Box<dyn Board>> = vec![
+        Box::new(game::Game::new(5, 5)),
+        Box::new(PackedGame::new(5, 5)),
+        Box::new(Viewport::new(SparseGame::new(), (0, 0), 5, 5)),
+        Box::new(Viewport::new(life, (0, 0), 5, 5)),
+    ];
+    for board in boards.iter_mut() {
+        board.set_rule(rule).unwrap();
+        board.set(2, 2, CellState::Alive);
+        board.step(3);
+        assert_eq!(board.live_cells(), [(2, 2)]);
+    }
+    let code = Apgcode::from_cells(&[(0, 0)], rule).unwrap();
+    assert_eq!(code.to_string(), "xs1_1");
+}
+
+#[test]
+fn rle_files() {
+    let glider = "#N Glider\n#O Richard K. Guy\n#C The smallest spaceship\n#C Found in 1969\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    let saved = save_load::parse_rle(glider).unwrap();
+    assert_eq!((saved.width, saved.height), (3, 3));
+    assert_eq!(saved.metadata.name.as_deref(), Some("Glider"));
+    assert_eq!(saved.metadata.author.as_deref(), Some("Richard K. Guy"));
+    assert_eq!(saved.metadata.comments.len(), 2);
+    let pattern = Catalogue::builtin().oriented("glider", &[]).unwrap();
+    let mut board = game::Game::new(3, 3);
+    board.paste(&pattern, (0, 0), PasteMode::Or);
+    assert_eq!(saved.cells, board.cells());
+    // Written back the same way
+    assert_eq!(
+        save_load::write_rle(&board, &saved.metadata).unwrap(),
+        glider
+    );
+
+    // Loading puts it on a board of at least the default size
+    std::fs::write("test-glider.rle", glider).unwrap();
+    let (loaded, metadata) =
+        save_load::load_with_metadata("test-glider.rle", game::Game::new).unwrap();
+    assert_eq!((loaded.x_max, loaded.y_max), (GAME_X, GAME_Y));
+    assert_eq!((loaded.population(), metadata), (5, saved.metadata.clone()));
+    assert_eq!(save_load::load_pattern("test-glider.rle").unwrap(), pattern);
+    std::fs::remove_file("test-glider.rle").unwrap();
+
+    // Long patterns are wrapped at 70 columns, & empty rows are joined up
+    let gun = Catalogue::builtin().oriented("gosper-gun", &[]).unwrap();
+    let mut board = game::Game::new(40, 14);
+    board.paste(&gun, (2, 3), PasteMode::Or);
+    board.set_topology(Topology::Torus);
+    save_load::save_game(&board, "test-gun.rle").unwrap();
+    let written = std::fs::read_to_string("test-gun.rle").unwrap();
+    assert!(written.lines().all(|l| l.len() <= 70));
+    assert!(written.lines().nth(1).unwrap().starts_with("3$"));
+    assert!(written.contains("rule = B3/S23:T40,14\n"));
+    let loaded = save_load::load_game("test-gun.rle").unwrap();
+    assert_eq!(loaded.cells(), board.cells());
+    assert_eq!(loaded.topology(), Topology::Torus);
+    // Growing it would change where it wraps, so it's left the size it was
+    let padded = save_load::load_game_padded("test-gun.rle").unwrap();
+    assert_eq!((padded.x_max, padded.y_max), (40, 14));
+    std::fs::remove_file("test-gun.rle").unwrap();
+    // The edges are the size of the board, the pattern goes in the middle
+    let saved = save_load::parse_rle("x = 3, y = 3, rule = B3/S23:T20,10\nbo$2bo$3o!").unwrap();
+    assert_eq!((saved.width, saved.height), (20, 10));
+    let mut expected = game::Game::new(20, 10);
+    expected.paste(&pattern, (8, 3), PasteMode::Or);
+    assert_eq!(saved.cells, expected.cells());
+    // Golly's empty pattern is a dead cell, grown like any other small pattern
+    let saved = save_load::parse_rle("x = 0, y = 0, rule = B3/S23\n!").unwrap();
+    assert_eq!((saved.width, saved.height), (1, 1));
+    std::fs::write("test-empty.rle", "x = 0, y = 0, rule = B3/S23\n!").unwrap();
+    let padded = save_load::load_game_padded("test-empty.rle").unwrap();
+    assert_eq!((padded.x_max, padded.y_max), (GAME_X, GAME_Y));
+    assert_eq!(padded.population(), 0);
+    std::fs::remove_file("test-empty.rle").unwrap();
+    // So are Klein bottles & cross-surfaces, but not borders of living cells
+    for (topology, edges) in [
+        (Topology::KleinBottle, ":K40*,14"),
+        (Topology::CrossSurface, ":C40,14"),
+    ] {
+        board.set_topology(topology);
+        let written = save_load::write_rle(&board, &save_load::Metadata::default()).unwrap();
+        assert!(written.contains(&format!("rule = B3/S23{edges}\n")));
+        let saved = save_load::parse_rle(&written).unwrap();
+        assert_eq!(
+            (saved.topology, saved.cells),
+            (topology, board.cells().to_vec())
+        );
+    }
+    board.set_topology(Topology::AliveBorder);
+    assert!(save_load::write_rle(&board, &save_load::Metadata::default()).is_err());
+    assert!(save_load::save_game(&board, "test-border.rle").is_err());
+
+    // Rules w/ more than 2 states use letters, w/ a prefix past 24
+    let rule = Rule::parse("B2/S/C30").unwrap();
+    let mut board = game::Game::new(4, 2);
+    board.set_rule(rule);
+    board.set(0, 0, CellState::Alive);
+    board.set(1, 0, CellState::from_state_index(2, 30));
+    board.set(2, 1, CellState::from_state_index(29, 30));
+    let written = save_load::write_rle(&board, &save_load::Metadata::default()).unwrap();
+    assert_eq!(written, "x = 4, y = 2, rule = B2/S/C30\nAB$2.pE!\n");
+    let saved = save_load::parse_rle(&written).unwrap();
+    assert_eq!((saved.rule, saved.cells), (rule, board.cells().to_vec()));
+    // LtL rules have commas in them
+    let saved = save_load::parse_rle("x = 1, y = 1, rule = R2,C0,M1,S2..3,B3..3,NM\no!").unwrap();
+    assert_eq!(saved.rule, Rule::parse("R2,C0,M1,S2..3,B3..3,NM").unwrap());
+
+    for broken in [
+        "",
+        "#C just a comment",
+        "x = 3\nbo!",
+        "x = 3, y = 3, rule = nope\nbo!",
+        "x = 2, y = 2\n3o!",
+        "x = 2, y = 2\n$$o!",
+        "x = 2, y = 2\nbz!",
+        "x = 2, y = 2, rule = B3/S23\nC!",
+        "x = 2, y = 2\n99999999999999999999999o!",
+        "x = 2, y = 2, rule = B3/S23:S2\no!",
+        "x = 2, y = 2, rule = B3/S23:K2,2*\no!",
+        "x = 2, y = 2, rule = B3/S23:T1,1\no!",
+        "x = 2, y = 2, rule = B3/S23:T0,10\no!",
+        "x = 2, y = 2, rule = B3/S23:T20+1,10\no!",
+        "x = 2, y = 2, rule = B3/S23:T\no!",
+        // The Kelvin sign is shorter in lowercase
+        "\u{212A} = 1, y = 1, 日rule = B3/S23\no!",
+        "x = 4294967296, y = 4294967296",
+        "x = 4000000000, y = 4000000000",
+        "x = 0, y = 0\no!",
+    ] {
+        assert!(save_load::parse_rle(broken).is_err(), "{broken:?}");
+    }
+}
+#[test]
+fn cells_files() {
+    let glider = "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship\n.O.\n..O\nOOO\n";
+    let saved = save_load::parse_cells(glider).unwrap();
+    assert_eq!((saved.width, saved.height), (3, 3));
+    assert_eq!(saved.metadata.name.as_deref(), Some("Glider"));
+    assert_eq!(saved.metadata.author.as_deref(), Some("Richard K. Guy"));
+    assert_eq!(saved.metadata.comments, ["The smallest spaceship"]);
+    let pattern = Catalogue::builtin().oriented("glider", &[]).unwrap();
+    let mut board = game::Game::new(3, 3);
+    board.paste(&pattern, (0, 0), PasteMode::Or);
+    assert_eq!(saved.cells, board.cells());
+    assert_eq!(save_load::write_cells(&board, &saved.metadata), glider);
+
+    // Short rows are padded w/ dead cells, blank lines are empty rows (but not at the end)
+    let saved = save_load::parse_cells("!comment\n.O\n\n*..O\n\n\n").unwrap();
+    assert_eq!((saved.width, saved.height), (4, 3));
+    let alive: Vec<usize> = (0..12)
+        .filter(|i| saved.cells[*i] == CellState::Alive)
+        .collect();
+    assert_eq!(alive, [1, 8, 11]);
+
+    for broken in ["", "!Name: nothing\n", "\n\n", ".O\nOx\n"] {
+        assert!(save_load::parse_cells(broken).is_err(), "{broken:?}");
+    }
+
+    // Converting keeps the metadata & never writes over the file being read
+    std::fs::write("test-glider.cells", glider).unwrap();
+    assert_eq!(
+        save_load::convert("test-glider.cells", "./test-glider.cells"),
+        Err(save_load::SaveLoadError::SameFile)
+    );
+    assert_eq!(
+        std::fs::read_to_string("test-glider.cells").unwrap(),
+        glider
+    );
+    save_load::convert("test-glider.cells", "test-glider-converted.rle").unwrap();
+    let (loaded, metadata) =
+        save_load::load_with_metadata("test-glider-converted.rle", game::Game::new).unwrap();
+    assert_eq!(metadata.name.as_deref(), Some("Glider"));
+    assert_eq!(loaded.population(), 5);
+    std::fs::remove_file("test-glider.cells").unwrap();
+    std::fs::remove_file("test-glider-converted.rle").unwrap();
+}
+#[test]
+fn life_files() {
+    let glider_106 = "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n";
+    let life = save_load::parse_life(glider_106).unwrap();
+    assert_eq!(life.cells[0], (0, -1));
+    // Fitting puts the pattern in the corner of a board just big enough
+    let pattern = Catalogue::builtin().oriented("glider", &[]).unwrap();
+    let mut board = game::Game::new(3, 3);
+    board.paste(&pattern, (0, 0), PasteMode::Or);
+    let saved = life.clone().fit().unwrap();
+    assert_eq!((saved.width, saved.height), (3, 3));
+    assert_eq!(saved.cells, board.cells());
+    // Or (0, 0) can go anywhere, as long as the cells stay on the board
+    let saved = life.clone().place((5, 5), (10, 10)).unwrap();
+    assert!(saved.cells[4 * 10 + 5] == CellState::Alive);
+    assert_eq!(
+        life.place((0, 0), (10, 10)),
+        Err(save_load::SaveLoadError::InvalidPattern)
+    );
+
+    // Written back relative to the chosen origin
+    assert_eq!(
+        save_load::write_life(
+            &board,
+            &save_load::Metadata::default(),
+            save_load::LifeVersion::V106,
+            (1, 1)
+        ),
+        glider_106
+    );
+
+    let glider_105 =
+        "#Life 1.05\n#D Name: Glider\n#D The smallest spaceship\n#N\n#P -1 -1\n.*\n..*\n***\n";
+    let life = save_load::parse_life(glider_105).unwrap();
+    assert_eq!(life.metadata.name.as_deref(), Some("Glider"));
+    assert_eq!(life.metadata.comments, ["The smallest spaceship"]);
+    assert_eq!(life.clone().fit().unwrap().cells, board.cells());
+    let written =
+        save_load::write_life(&board, &life.metadata, save_load::LifeVersion::V105, (1, 1));
+    assert_eq!(written, glider_105);
+    // Several blocks, empty rows & other rules
+    let life =
+        save_load::parse_life("#Life 1.05\n#R 23/36\n#P -3 0\n*\n.\n*\n#P 10 -2\n.*\n").unwrap();
+    assert_eq!(life.rule, Rule::parse("B36/S23").unwrap());
+    // Written back survival first, like it was read
+    let mut highlife = game::Game::new(3, 3);
+    highlife.set_rule(life.rule);
+    let written = save_load::write_life(
+        &highlife,
+        &save_load::Metadata::default(),
+        save_load::LifeVersion::V105,
+        (0, 0),
+    );
+    assert_eq!(written, "#Life 1.05\n#R 23/36\n");
+    let mut generations = game::Game::new(3, 3);
+    generations.set_rule(Rule::parse("B2/S/C3").unwrap());
+    let written = save_load::write_life(
+        &generations,
+        &save_load::Metadata::default(),
+        save_load::LifeVersion::V105,
+        (0, 0),
+    );
+    assert_eq!(written, "#Life 1.05\n#R B2/S/C3\n");
+    assert_eq!(life.cells, [(-3, 0), (-3, 2), (11, -2)]);
+    let saved = life.fit().unwrap();
+    assert_eq!((saved.width, saved.height), (15, 5));
+
+    for broken in [
+        "",
+        "0 0\n",
+        "#Life 2.0\n0 0\n",
+        "#Life 1.06\n0\n",
+        "#Life 1.06\n0 x\n",
+        "#Life 1.05\n#P 0\n*\n",
+        "#Life 1.05\n.x\n",
+        "#Life 1.05\n#R nope\n*\n",
+    ] {
+        assert!(save_load::parse_life(broken).is_err(), "{broken:?}");
+    }
+    let far_apart =
+        save_load::parse_life("#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0\n");
+    assert!(far_apart.unwrap().fit().is_err());
+    let huge = save_load::parse_life("#Life 1.06\n0 0\n4000000000 4000000000\n").unwrap();
+    assert_eq!(
+        huge.clone().fit().err(),
+        Some(save_load::SaveLoadError::TooBig)
+    );
+    let size = (4_000_000_001, 4_000_000_001);
+    assert_eq!(
+        huge.place((0, 0), size).err(),
+        Some(save_load::SaveLoadError::TooBig)
+    );
+
+    // Saving as .lif & loading again
+    let mut board = game::Game::new(20, 20);
+    board.paste(&pattern, (15, 2), PasteMode::Or);
+    save_load::save_game(&board, "test-glider.lif").unwrap();
+    let loaded = save_load::load_game("test-glider.lif").unwrap();
+    assert_eq!((loaded.x_max, loaded.population()), (3, 5));
+    let (loaded, _) =
+        save_load::load_life("test-glider.lif", (10, 10), (20, 20), game::Game::new).unwrap();
+    assert_eq!(loaded.cells(), board.cells());
+    assert_eq!(
+        save_load::read_coords_from_file("test-glider.lif"),
+        [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]
+    );
+    std::fs::remove_file("test-glider.lif").unwrap();
+}
+#[test]
+fn macrocell_files() {
+    let glider = "[M2] (golly 4.2)\n#N Glider\n#R B3/S23\n#G 8\n.*$..*$***$\n4 1 0 0 0\n";
+    let (life, metadata) = save_load::parse_macrocell(glider).unwrap();
+    assert_eq!(metadata.name.as_deref(), Some("Glider"));
+    assert_eq!((life.generation(), life.population()), (8, 5));
+    // The biggest square has its middle at (0, 0)
+    assert_eq!(life.bounding_box(), Some(((-8, -8), (-6, -6))));
+    assert_eq!(life.get(-7, -8), CellState::Alive);
+
+    // Written back the same way, & read onto a board just big enough
+    assert_eq!(
+        save_load::write_macrocell(&life, &metadata).lines().nth(1),
+        Some("#N Glider")
+    );
+    assert!(save_load::write_macrocell(&life, &metadata).ends_with(".*$..*$***$\n4 1 0 0 0\n"));
+    std::fs::write("test-glider.mc", glider).unwrap();
+    let board = save_load::load_game("test-glider.mc").unwrap();
+    let pattern = Catalogue::builtin().oriented("glider", &[]).unwrap();
+    let mut expected = game::Game::new(3, 3);
+    expected.paste(&pattern, (0, 0), PasteMode::Or);
+    assert_eq!(board.cells(), expected.cells());
+    std::fs::remove_file("test-glider.mc").unwrap();
+
+    // Cells stay where they are, wherever the engine's squares happen to line up
+    let gun = Catalogue::builtin().oriented("gosper-gun", &[]).unwrap();
+    let mut game = game::Game::new(40, 20);
+    game.paste(&gun, (3, 5), PasteMode::Or);
+    let mut life = HashLife::from_game(&game).unwrap();
+    life.step(123);
+    let (read, _) = save_load::parse_macrocell(&save_load::write_macrocell(
+        &life,
+        &save_load::Metadata::default(),
+    ))
+    .unwrap();
+    let ((x0, y0), (x1, y1)) = life.bounding_box().unwrap();
+    assert_eq!(read.bounding_box(), life.bounding_box());
+    assert_eq!(read.generation(), 123);
+    let size = ((x1 - x0 + 1) as usize, (y1 - y0 + 1) as usize);
+    assert_eq!(
+        read.to_game((x0, y0), size.0, size.1).cells(),
+        life.to_game((x0, y0), size.0, size.1).cells()
+    );
+
+    // Too big for a board, but not for HashLife
+    let mut life = HashLife::new(Rule::parse("B36/S23").unwrap()).unwrap();
+    life.set(-3, 0, CellState::Alive);
+    life.set(100_000, 5, CellState::Alive);
+    let written = save_load::write_macrocell(&life, &save_load::Metadata::default());
+    std::fs::write("test-far.mc", written).unwrap();
+    assert_eq!(
+        save_load::load_game("test-far.mc").err(),
+        Some(save_load::SaveLoadError::TooBig)
+    );
+    let contents = std::fs::read_to_string("test-far.mc").unwrap();
+    let (loaded, _) = save_load::parse_macrocell(&contents).unwrap();
+    assert_eq!(loaded.rule(), life.rule());
+    assert_eq!(loaded.bounding_box(), Some(((-3, 0), (100_000, 5))));
+    std::fs::remove_file("test-far.mc").unwrap();
+
+    // Any board can be saved as one
+    save_load::save_game(&expected, "test-board.mc").unwrap();
+    assert_eq!(
+        save_load::load_game("test-board.mc").unwrap().cells(),
+        expected.cells()
+    );
+    std::fs::remove_file("test-board.mc").unwrap();
+
+    for broken in [
+        "",
+        ".*$\n",
+        "[M2]\n4 2 0 0 0\n.*$\n",
+        "[M2]\n.*$\n5 1 0 0 0\n",
+        "[M2]\n.x$\n",
+        "[M2]\n.........*$\n",
+        "[M2]\n4 1 0 0\n",
+        "[M2]\n#R B3/S23/C3\n.*$\n",
+        "[M2]\n#G soon\n.*$\n",
+    ] {
+        assert!(save_load::parse_macrocell(broken).is_err(), "{broken:?}");
+    }
+}
+#[test]
+fn apgcodes() {
+    let catalogue = Catalogue::builtin();
+    let code_of = |name: &str, orientation: &[Transform]| {
+        let pattern = catalogue.oriented(name, orientation).unwrap();
+        let mut game = game::Game::new(pattern.width() + 4, pattern.height() + 4);
+        game.paste(&pattern, (2, 2), PasteMode::Or);
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: game.x_max,
+            height: game.y_max,
+        };
+        Apgcode::from_region(&game, area).map(|code| code.to_string())
+    };
+    for (name, code) in [
+        ("block", "xs4_33"),
+        ("beehive", "xs6_696"),
+        ("boat", "xs5_253"),
+        ("loaf", "xs7_2596"),
+        ("blinker", "xp2_7"),
+        ("toad", "xp2_7e"),
+        ("beacon", "xp2_318c"),
+        ("pulsar", "xp3_co9nas0san9oczgoldlo0oldlogz1047210127401"),
+        ("pentadecathlon", "xp15_4r4z4r4"),
+        ("glider", "xq4_153"),
+        ("lwss", "xq4_6frc"),
+        ("mwss", "xq4_27dee6"),
+        ("hwss", "xq4_27deee6"),
+    ] {
+        // The same code whichever way the object is turned
+        assert_eq!(code_of(name, &[]).as_deref(), Ok(code), "{name}");
+        let turned = [Transform::Rotate90, Transform::FlipHorizontal];
+        assert_eq!(code_of(name, &turned).as_deref(), Ok(code), "{name}");
+
+        // & back again, to an object w/ the same code
+        let parsed: Apgcode = code.parse().unwrap();
+        assert_eq!(parsed.to_string(), code);
+        let game = parsed.to_game(Rule::default());
+        let cells: Vec<(i64, i64)> = game
+            .live_cells()
+            .into_iter()
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect();
+        assert_eq!(Apgcode::from_cells(&cells, Rule::default()), Ok(parsed));
+    }
+    let glider: Apgcode = "xq4_153".parse().unwrap();
+    assert_eq!((glider.kind, glider.number), (ObjectKind::Spaceship, 4));
+    assert_eq!(glider.cells(), [(0, 0), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    // Runs of empty columns
+    let far: Apgcode = "xp2_7y17".parse().unwrap();
+    assert_eq!(far.cells().last(), Some(&(6, 2)));
+
+    assert!(code_of("r-pentomino", &[]).is_err());
+    assert!(code_of("diehard", &[]).is_err());
+    assert!(Apgcode::from_cells(&[], Rule::default()).is_err());
+    for broken in [
+        "", "33", "xs_33", "xs0_33", "xr4_33", "xs4_", "xs4_3A", "xs4_3y",
+    ] {
+        assert!(broken.parse::<Apgcode>().is_err(), "{broken:?}");
+    }
+}
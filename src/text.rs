@@ -1,14 +1,33 @@
+use crate::board::{self, Board};
+use crate::catalogue::{Catalogue, CatalogueCommand};
+use crate::cycle::Cycle;
 use crate::game;
 use crate::game::GameAction;
+use crate::pattern::{PasteMode, Pattern, Rect, Transform};
+use crate::resize::{Anchor, Resize};
+use crate::rule::Rule;
+use crate::soup::Soup;
+use crate::stats::Stats;
+use crate::topology::Topology;
 use crate::{save_load, GAME_X, GAME_Y};
 use lazy_static::lazy_static;
 use regex::Regex;
-pub(crate) fn text() -> ! {
+/// Runs the game in the terminal, starting w/ the soup if there is one. Boards are
+/// made w/ 'new_board', so any engine can be used
+pub(crate) fn text<B: Board>(soup: Option<Soup>, new_board: fn(usize, usize) -> B) -> ! {
     println!("Welcome to the Game of Life!");
-    let start = initialize_board();
+    let start = match soup {
+        Some(soup) => {
+            let mut board = new_board(GAME_X, GAME_Y);
+            board.apply_soup(&soup).unwrap_or_else(|e| eprintln!("{e}"));
+            println!("Made the soup: {soup}");
+            board
+        }
+        None => initialize_board(new_board),
+    };
     run_command_line(start);
 }
-fn initialize_board() -> game::GameBoardOld {
+fn initialize_board<B: Board>(new_board: fn(usize, usize) -> B) -> B {
     let std_in = std::io::stdin();
     println!("Start (m)anually or (l)oad from file? (\"Enter\" to skip)");
 
@@ -18,41 +37,159 @@ fn initialize_board() -> game::GameBoardOld {
     match input.trim() {
         "l" => {
             let p = get_file_path();
-            save_load::load_board_from_file(p.trim())
+            let loaded = match get_user_life_origin(&std_in, p.trim()) {
+                Some(origin) => save_load::load_life(p.trim(), origin, (GAME_X, GAME_Y), new_board),
+                // Small patterns get room to grow
+                None => save_load::load_with_metadata(p.trim(), new_board),
+            };
+            match loaded {
+                Ok((board, metadata)) => {
+                    print_metadata(&metadata);
+                    board
+                }
+                Err(e) => {
+                    eprintln!("Failed to load board from file: {e:?}");
+                    new_board(GAME_X, GAME_Y)
+                }
+            }
         }
         "m" => {
-            let mut new_board = game::GameBoardOld::new(GAME_X, GAME_Y);
-            new_board.set_cells(get_coordinates(&std_in), game::CellState::Alive);
-            new_board
+            let mut board = new_board(GAME_X, GAME_Y);
+            if let Err(e) = board.set_topology(get_user_topology(&std_in)) {
+                eprintln!("{e}");
+            }
+            set_cells(&mut board, get_coordinates(&std_in), game::CellState::Alive);
+            board
         }
-        _ => game::GameBoardOld::new(GAME_X, GAME_Y),
+        _ => {
+            let mut board = new_board(GAME_X, GAME_Y);
+            if let Err(e) = board.set_topology(get_user_topology(&std_in)) {
+                eprintln!("{e}");
+            }
+            board
+        }
+    }
+}
+/// Asks where the (0, 0) of a Life file should go on the board, as their coordinates
+/// can be negative. None for other files, or to fit the board to the pattern
+fn get_user_life_origin(std_in: &std::io::Stdin, path: &str) -> Option<(i64, i64)> {
+    if save_load::Format::from_path(path) != save_load::Format::Life {
+        return None;
+    }
+    println!("Where should the file's 0,0 go on the board? x,y (\"Enter\" to fit the board to it)");
+    let (x, y) = get_user_coordinates(std_in).first().copied()?;
+    Some((x as i64, y as i64))
+}
+/// Prints the name, author & comments of a loaded pattern, if it has them
+fn print_metadata(metadata: &save_load::Metadata) {
+    if let Some(name) = &metadata.name {
+        println!("Loaded \"{name}\"");
+    }
+    if let Some(author) = &metadata.author {
+        println!("By {author}");
+    }
+    for comment in &metadata.comments {
+        println!("{comment}");
     }
 }
-fn run_command_line(mut board: game::GameBoardOld) -> ! {
+/// Runs the text UI on any kind of board. Undoing, the generation & cycles are up to
+/// the board, engines w/o them can't undo or spot cycles
+fn run_command_line(mut board: impl Board) -> ! {
     let std_in = std::io::stdin();
+    let mut clipboard: Option<Pattern> = None;
+    let mut catalogue = Catalogue::default();
 
     loop {
         match get_user_game_action(&std_in) {
-            GameAction::Step => board = game::run_iterations(&board, get_user_number(&std_in)),
+            GameAction::Step => board.step(get_user_number(&std_in)),
             GameAction::GrowCell => prompt_user_to_change_cells(&mut board, game::CellState::Alive),
             GameAction::KillCell => prompt_user_to_change_cells(&mut board, game::CellState::Dead),
-
+            GameAction::Undo => {
+                if !board.undo() {
+                    println!("Nothing to undo!");
+                }
+            }
+            GameAction::Redo => {
+                if !board.redo() {
+                    println!("Nothing to redo!");
+                }
+            }
             GameAction::Play => {
                 // "Play" the simulation until stopped, or everything dies
+                let stop_on_cycle =
+                    get_user_yes_no(&std_in, "Stop once the board is still or repeating?");
                 println!("The sim will run until all cells are dead, use ^C to stop.");
-                let mut count = 0;
-                while board.has_alive_cells() {
-                    display_next_iteration(&board, count > 0, count);
-                    board = game::run_iterations(&board, 1);
-                    count += 1;
+                let first = board.generation();
+                while board.population() > 0 {
+                    let cycle = board.cycle();
+                    let replace_prev = board.generation() > first;
+                    display_next_iteration(&board, replace_prev, board.stats(), cycle);
+                    if stop_on_cycle && cycle.is_some() {
+                        break;
+                    }
+                    board.step(1);
                     std::thread::sleep(std::time::Duration::from_millis(250));
                 }
-                println!("All Cells died:\n{}", board);
-                std::process::exit(0);
+                if board.population() == 0 {
+                    println!("All Cells died:\n{}", board::render(&board));
+                    std::process::exit(0);
+                }
+            }
+            GameAction::Resize => {
+                if let Some(resize) = get_user_resize(&std_in) {
+                    board.resize(resize).unwrap_or_else(|e| eprintln!("{e}"));
+                }
+            }
+            GameAction::Clipboard => {
+                prompt_user_clipboard(&std_in, &mut board, &mut clipboard);
+            }
+            GameAction::Catalogue => match get_user_catalogue_command(&std_in) {
+                Some(CatalogueCommand::Place {
+                    name,
+                    at: Some(at),
+                    orientation,
+                }) => match catalogue.oriented(&name, &orientation) {
+                    Ok(pattern) => {
+                        let mut cells = board.cells();
+                        pattern.paste_onto(&mut cells, board.width(), at, PasteMode::Or);
+                        board.replace_cells(&cells).expect("pasting keeps the size");
+                    }
+                    Err(e) => eprintln!("{e}"),
+                },
+                Some(CatalogueCommand::Place { at: None, .. }) => {
+                    eprintln!("Where should it go? eg. place glider 10,10")
+                }
+                Some(command) => run_catalogue_command(&mut catalogue, command),
+                None => {}
+            },
+            GameAction::Randomize => {
+                if let Some(soup) = get_user_soup(&std_in) {
+                    let mut cells = board.cells();
+                    match soup.paste_onto(&mut cells, board.width()) {
+                        Ok(_) => {
+                            board
+                                .replace_cells(&cells)
+                                .expect("the soup keeps the size");
+                            println!("Made the soup: {soup}");
+                        }
+                        Err(e) => eprintln!("{e}"),
+                    }
+                }
             }
             GameAction::Save => user_save_board(&board),
+            GameAction::ChangeRule => {
+                if let Some(rule) = get_user_rule(&std_in) {
+                    board.set_rule(rule).unwrap_or_else(|e| eprintln!("{e}"));
+                }
+            }
+            GameAction::ChangeTopology => {
+                let topology = get_user_topology(&std_in);
+                board
+                    .set_topology(topology)
+                    .unwrap_or_else(|e| eprintln!("{e}"));
+            }
             GameAction::PrintBoard => {
-                println!("{}", board)
+                println!("{}", board::render(&board))
             }
             GameAction::Quit => std::process::exit(0),
             GameAction::Paused => println!("Game is Paused!"),
@@ -62,7 +199,7 @@ fn run_command_line(mut board: game::GameBoardOld) -> ! {
 }
 
 /// Saves the board to the specified file
-fn user_save_board(board: &game::GameBoardOld) {
+fn user_save_board(board: &impl Board) {
     println!("Where would you like to save the board?");
     let p = get_file_path();
     save_load::save_board(p.trim(), board);
@@ -70,7 +207,7 @@ fn user_save_board(board: &game::GameBoardOld) {
 
 /// Prompts a user to pick cells to change on the board & changes them to the specified Status
 /// Allows for both file reading and manually typing in cells
-pub(crate) fn prompt_user_to_change_cells(board: &mut game::GameBoardOld, status: game::CellState) {
+pub(crate) fn prompt_user_to_change_cells(board: &mut impl Board, status: game::CellState) {
     let std_in = std::io::stdin();
     println!("(t)ype in coordinates or (r)ead from a file?");
 
@@ -78,22 +215,190 @@ pub(crate) fn prompt_user_to_change_cells(board: &mut game::GameBoardOld, status
     std_in.read_line(&mut input).expect("Failed reading stdIn");
 
     match input.trim() {
-        "t" => board.set_cells(get_coordinates(&std_in), status),
+        "t" => set_cells(board, get_coordinates(&std_in), status),
         "r" => {
             let p = get_file_path();
-            board.set_cells(save_load::read_coords_from_file(p.trim()), status);
+            set_cells(board, save_load::read_coords_from_file(p.trim()), status);
         }
         _ => eprintln!("Error, No Cells Changed."),
     }
 }
-/// Prints the board to the terminal, replacing previous text if replace_prev is true
-fn display_next_iteration(board: &game::GameBoardOld, replace_prev: bool, gen: i32) {
+/// Sets each of the cells to 'status' (as one edit), coordinates past the edges wrap
+/// around
+fn set_cells(board: &mut impl Board, cells: Vec<(usize, usize)>, status: game::CellState) {
+    let (width, height) = board.size();
+    let cells: Vec<_> = cells.iter().map(|(x, y)| (x % width, y % height)).collect();
+    board.set_many(&cells, &[status]);
+}
+/// Prompts the user to copy, cut or paste a rectangle of the board, or to turn the
+/// pattern that's on the clipboard
+fn prompt_user_clipboard(
+    std_in: &std::io::Stdin,
+    board: &mut impl Board,
+    clipboard: &mut Option<Pattern>,
+) {
+    println!("(c)opy, cu(t) or (p)aste a region, or (r)otate/(f)lip the copied one?");
+    let mut input: String = String::new();
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    let mut cells = board.cells();
+    match input.trim() {
+        action @ ("c" | "t") => {
+            let Some(area) = get_user_region(std_in) else {
+                return;
+            };
+            let Some(area) = area.clip(board.width(), board.height()) else {
+                eprintln!("The region isn't on the board!");
+                return;
+            };
+            let pattern = Pattern::copy_from(&cells, board.width(), area);
+            if action == "t" {
+                let dead = Pattern::dead(area.width, area.height);
+                dead.paste_onto(
+                    &mut cells,
+                    board.width(),
+                    (area.x, area.y),
+                    PasteMode::Overwrite,
+                );
+                board.replace_cells(&cells).expect("cutting keeps the size");
+            }
+            println!(
+                "Copied a {} by {} region",
+                pattern.width(),
+                pattern.height()
+            );
+            *clipboard = Some(pattern);
+        }
+        "p" => {
+            let Some(pattern) = clipboard else {
+                eprintln!("Nothing has been copied!");
+                return;
+            };
+            println!("Enter the top left corner to paste at: x,y");
+            let Some(at) = get_user_coordinates(std_in).first().copied() else {
+                eprintln!("Couldn't parse the corner");
+                return;
+            };
+            println!("Paste w/ (o)r, (x)or or over(w)rite? (\"Enter\" for or)");
+            let mut input: String = String::new();
+            std_in.read_line(&mut input).expect("Failed reading stdIn");
+            let mode = match input.trim() {
+                "" => PasteMode::default(),
+                mode => match mode.parse() {
+                    Ok(mode) => mode,
+                    Err(e) => {
+                        eprintln!("{e}");
+                        return;
+                    }
+                },
+            };
+            pattern.paste_onto(&mut cells, board.width(), at, mode);
+            board.replace_cells(&cells).expect("pasting keeps the size");
+        }
+        action @ ("r" | "f") => {
+            let Some(pattern) = clipboard else {
+                eprintln!("Nothing has been copied!");
+                return;
+            };
+            match action {
+                "r" => println!("Rotate clockwise by 90, 180 or 270 degrees?"),
+                _ => println!("Flip (h)orizontally or (v)ertically?"),
+            }
+            let mut input: String = String::new();
+            std_in.read_line(&mut input).expect("Failed reading stdIn");
+            match input.parse::<Transform>() {
+                Ok(transform) => *pattern = pattern.transformed(transform),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        _ => eprintln!("Error, nothing was copied or pasted."),
+    }
+}
+/// Prompts the user for the settings of a random soup.
+/// Parsing errors are logged to std err, and None is returned
+pub(crate) fn get_user_soup(std_in: &std::io::Stdin) -> Option<Soup> {
+    let mut input: String = String::new();
+    println!("Enter the soup's settings, eg. seed=1234 density=0.5 symmetry=C2 region=0,0,19,19");
+    println!("(\"Enter\" for a random seed, half full, no symmetry & the whole board)");
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    match input.parse() {
+        Ok(soup) => Some(soup),
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+/// Prompts the user for something to do w/ the pattern catalogue, like
+/// "place glider 10,10 rot90". Parsing errors are logged to std err, and None is returned
+pub(crate) fn get_user_catalogue_command(std_in: &std::io::Stdin) -> Option<CatalogueCommand> {
+    let mut input: String = String::new();
+    println!("Enter \"place <name> x,y [rot90/rot180/rot270/flip-h/flip-v]\", \"list\" or \"add <name> <file path> [description]\":");
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    match input.parse() {
+        Ok(command) => Some(command),
+        Err(e) => {
+            eprintln!("{e}");
+            None
+        }
+    }
+}
+/// Lists or adds patterns in the catalogue, placing them is up to the caller (as
+/// that depends on the board)
+pub(crate) fn run_catalogue_command(catalogue: &mut Catalogue, command: CatalogueCommand) {
+    match command {
+        CatalogueCommand::List => print!("{catalogue}"),
+        CatalogueCommand::Add {
+            name,
+            path,
+            description,
+        } => match save_load::load_pattern(&path) {
+            Ok(pattern) => {
+                catalogue.add(&name, &description, pattern);
+                println!("Added \"{name}\" to the catalogue");
+            }
+            Err(e) => eprintln!("Couldn't load a pattern from the file: {e:?}"),
+        },
+        CatalogueCommand::Place { .. } => {}
+    }
+}
+/// Prompts the user for a rectangle of the board, by 2 of its opposite corners
+fn get_user_region(std_in: &std::io::Stdin) -> Option<Rect> {
+    println!("Enter 2 opposite corners of the region: x,y x,y");
+    match get_user_coordinates(std_in)[..] {
+        [a, b, ..] => Some(Rect::from_corners(a, b)),
+        _ => {
+            eprintln!("Couldn't parse 2 corners");
+            None
+        }
+    }
+}
+/// Reads coordinates from a line of std in, w/o prompting for them
+fn get_user_coordinates(std_in: &std::io::Stdin) -> Vec<(usize, usize)> {
+    let mut input: String = String::new();
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+    parse_to_coordinates(input)
+}
+/// Prints the board to the terminal w/ its stats, replacing previous text if
+/// replace_prev is true. If the board is in a cycle it is noted after the stats
+fn display_next_iteration(
+    board: &impl Board,
+    replace_prev: bool,
+    stats: Stats,
+    cycle: Option<Cycle>,
+) {
     if replace_prev {
-        for _ in 0..=board.y_max {
+        for _ in 0..=board.height() {
             print!("{}", ansi_escapes::CursorPrevLine);
         }
     }
-    println!("Generation: {gen}\n{board}");
+    match cycle {
+        Some(cycle) => println!("{stats} ({cycle}){}", ansi_escapes::EraseEndLine),
+        None => println!("{stats}{}", ansi_escapes::EraseEndLine),
+    }
+    println!("{}", board::render(board));
 }
 
 /// Prompts the user for any number of coordinates
@@ -124,28 +429,108 @@ pub(crate) fn parse_to_coordinates(input: String) -> Vec<(usize, usize)> {
     cells
 }
 
+/// Asks the user a yes or no question, anything other than "y" is a no
+pub(crate) fn get_user_yes_no(std_in: &std::io::Stdin, question: &str) -> bool {
+    let mut input: String = String::new();
+    println!("{question} (y/n)");
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+    input.trim().eq_ignore_ascii_case("y")
+}
 /// Prompts the user for a single number
 pub(crate) fn get_user_number(std_in: &std::io::Stdin) -> usize {
     let mut input: String = String::new();
     println!("Please enter a number:");
     std_in.read_line(&mut input).expect("Failed reading stdIn");
 
-    return match input.trim().parse() {
+    match input.trim().parse() {
         Ok(num) => num,
         Err(_) => {
             eprintln!("Couldn't parse number");
             0
         }
-    };
+    }
+}
+/// Prompts the user for a rulestring, like B3/S23
+/// Parsing errors are logged to std err, and None is returned
+pub(crate) fn get_user_rule(std_in: &std::io::Stdin) -> Option<Rule> {
+    let mut input: String = String::new();
+    println!("Please enter a rule (eg. B3/S23, B2/S/C3, B2-a/S12, B2/S34H, R5,C0,M1,S34..58,B34..45,NM):");
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    match Rule::parse(&input) {
+        Ok(rule) => Some(rule),
+        Err(e) => {
+            eprintln!("Couldn't parse rule: {e}");
+            None
+        }
+    }
+}
+/// Prompts the user for a new size for the board, by resizing, padding or cropping
+/// it. Parsing errors are logged to std err, and None is returned
+pub(crate) fn get_user_resize(std_in: &std::io::Stdin) -> Option<Resize> {
+    let mut input: String = String::new();
+    println!("(r)esize, (p)ad or (c)rop the board to the living cells?");
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    match input.trim() {
+        "r" => {
+            println!("Enter the new size: width,height");
+            input.clear();
+            std_in.read_line(&mut input).expect("Failed reading stdIn");
+            let Some((width, height)) = parse_to_coordinates(input).first().copied() else {
+                eprintln!("Couldn't parse the size");
+                return None;
+            };
+            println!("Pick the part of the board to keep in place: (tl) (t) (tr) (l) (c)enter (r) (bl) (b) (br)");
+            let mut input: String = String::new();
+            std_in.read_line(&mut input).expect("Failed reading stdIn");
+            if input.trim().is_empty() {
+                return Some(Resize::To(width, height, Anchor::default()));
+            }
+            match input.parse() {
+                Ok(anchor) => Some(Resize::To(width, height, anchor)),
+                Err(e) => {
+                    eprintln!("{e}");
+                    None
+                }
+            }
+        }
+        "p" => Some(Resize::Pad(get_user_number(std_in))),
+        "c" => Some(Resize::CropToLive),
+        _ => {
+            eprintln!("Error, the board wasn't resized.");
+            None
+        }
+    }
+}
+/// Prompts the user for how the edges of the board behave
+/// Anything that isn't a topology (including just "Enter") gives a bounded board
+pub(crate) fn get_user_topology(std_in: &std::io::Stdin) -> Topology {
+    let mut input: String = String::new();
+    println!(
+        "Pick the board edges: (b)ounded, (t)orus, (k)lein bottle, (c)ross-surface, (a)live border"
+    );
+    std_in.read_line(&mut input).expect("Failed reading stdIn");
+
+    if input.trim().is_empty() {
+        return Topology::default();
+    }
+    match input.parse() {
+        Ok(topology) => topology,
+        Err(e) => {
+            eprintln!("{e}, using a bounded board");
+            Topology::default()
+        }
+    }
 }
 /// Reads a line from the console & parses it into a Action
 pub fn get_user_game_action(std_in: &std::io::Stdin) -> GameAction {
     println!("Pick an option:");
-    println!("(s)imulate, (g)row/(k)ill cells, (p)rint the board, (l)et the sim. run, sa(v)e the board, (q)uit/(c)ancel");
+    println!("(s)imulate, (g)row/(k)ill cells, (p)rint the board, (l)et the sim. run, sa(v)e the board, change the (r)ule/(t)opology, (u)ndo/re(d)o, resi(z)e, clip(b)oard, p(a)tterns, ra(n)dom soup, (q)uit/(c)ancel");
     let mut input: String = String::new();
     std_in.read_line(&mut input).expect("Failed reading stdIn");
 
-    return match input.trim() {
+    match input.trim() {
         "s" => GameAction::Step,
         "p" => GameAction::PrintBoard,
         "g" => GameAction::GrowCell,
@@ -153,13 +538,21 @@ pub fn get_user_game_action(std_in: &std::io::Stdin) -> GameAction {
         "q" | "c" => GameAction::Quit,
         "l" => GameAction::Play,
         "v" => GameAction::Save,
+        "r" => GameAction::ChangeRule,
+        "t" => GameAction::ChangeTopology,
+        "u" => GameAction::Undo,
+        "d" => GameAction::Redo,
+        "z" => GameAction::Resize,
+        "b" => GameAction::Clipboard,
+        "a" => GameAction::Catalogue,
+        "n" => GameAction::Randomize,
         _ => GameAction::Failed,
-    };
+    }
 }
 
 pub(crate) fn get_file_path() -> String {
     let mut s = String::new();
-    println!("Please enter a file path:");
+    println!("Please enter a file path (files ending in .rle, .cells, .lif or .mc are read & written in that format):");
     match std::io::stdin().read_line(&mut s) {
         Ok(_) => {}
         Err(e) => {
@@ -0,0 +1,152 @@
+use std::fmt::Formatter;
+
+use crate::game::CellState;
+
+/// What happens to neighbors that would fall off the edge of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// Everything past the edge is dead
+    #[default]
+    Bounded,
+    /// Left & right edges are joined, as are the top & bottom
+    Torus,
+    /// Like a torus, but going over the top or bottom edge mirrors the x position
+    KleinBottle,
+    /// Going over any edge mirrors the position along that edge (a real projective plane)
+    CrossSurface,
+    /// Everything past the edge is alive
+    AliveBorder,
+    /// There are no edges, the board is a window onto an infinite one (see
+    /// [crate::board::Viewport]). Boards w/ a size only know what's on them, so they
+    /// treat it like [Topology::Bounded]
+    Unbounded,
+}
+impl Topology {
+    /// The edges a board w/ a size can have
+    #[allow(unused)]
+    pub const ALL: [Topology; 5] = [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::KleinBottle,
+        Topology::CrossSurface,
+        Topology::AliveBorder,
+    ];
+    /// Maps a position that might be off the board back onto it.
+    /// Returns None if the position isn't on the board, see [Topology::border_state]
+    /// for what those cells are
+    pub fn resolve(
+        &self,
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let (w, h) = (width as isize, height as isize);
+        let off_x = !(0..w).contains(&x);
+        let off_y = !(0..h).contains(&y);
+
+        let (x, y) = match self {
+            Topology::Bounded | Topology::AliveBorder | Topology::Unbounded if off_x || off_y => {
+                return None
+            }
+            Topology::Bounded | Topology::AliveBorder | Topology::Unbounded | Topology::Torus => {
+                (x, y)
+            }
+            Topology::KleinBottle if off_y => (w - 1 - x, y),
+            Topology::KleinBottle => (x, y),
+            Topology::CrossSurface => (
+                if off_y { w - 1 - x } else { x },
+                if off_x { h - 1 - y } else { y },
+            ),
+        };
+        Some((x.rem_euclid(w) as usize, y.rem_euclid(h) as usize))
+    }
+    /// Whether cells past one edge come back on another edge
+    pub fn wraps(&self) -> bool {
+        !matches!(
+            self,
+            Topology::Bounded | Topology::AliveBorder | Topology::Unbounded
+        )
+    }
+    /// The state of a cell that isn't on the board
+    pub fn border_state(&self) -> CellState {
+        match self {
+            Topology::AliveBorder => CellState::Alive,
+            _ => CellState::Dead,
+        }
+    }
+    /// The state of the cell at (x + dx, y + dy), 'cell' is used to look up cells on the board
+    fn neighbor(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+        (width, height): (usize, usize),
+        cell: &impl Fn(usize, usize) -> CellState,
+    ) -> CellState {
+        match self.resolve(x as isize + dx, y as isize + dy, width, height) {
+            Some((nx, ny)) => cell(nx, ny),
+            None => self.border_state(),
+        }
+    }
+    /// Counts how many of the cells at the given offsets from (x, y) are alive.
+    /// 'cell' is used to look up cells on the board
+    pub fn alive_neighbors(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        offsets: &[(isize, isize)],
+        cell: impl Fn(usize, usize) -> CellState,
+    ) -> usize {
+        offsets
+            .iter()
+            .filter(|d| self.neighbor((x, y), **d, (width, height), &cell) == CellState::Alive)
+            .count()
+    }
+    /// Like [Topology::alive_neighbors], but says which neighbors are alive:
+    /// bit i is set if the cell at offsets\[i\] is alive. Only the first 64 offsets are used
+    pub fn neighbor_mask(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        offsets: &[(isize, isize)],
+        cell: impl Fn(usize, usize) -> CellState,
+    ) -> u64 {
+        let mut mask = 0;
+        for (i, d) in offsets.iter().take(64).enumerate() {
+            if self.neighbor((x, y), *d, (width, height), &cell) == CellState::Alive {
+                mask |= 1 << i;
+            }
+        }
+        mask
+    }
+}
+impl std::fmt::Display for Topology {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Topology::Bounded => "bounded",
+            Topology::Torus => "torus",
+            Topology::KleinBottle => "klein",
+            Topology::CrossSurface => "cross",
+            Topology::AliveBorder => "alive",
+            Topology::Unbounded => "unbounded",
+        };
+        write!(f, "{name}")
+    }
+}
+impl std::str::FromStr for Topology {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "bounded" | "b" => Ok(Topology::Bounded),
+            "torus" | "t" => Ok(Topology::Torus),
+            "klein" | "k" => Ok(Topology::KleinBottle),
+            "cross" | "c" => Ok(Topology::CrossSurface),
+            "alive" | "a" => Ok(Topology::AliveBorder),
+            _ => Err("unknown topology, expected bounded, torus, klein, cross or alive"),
+        }
+    }
+}
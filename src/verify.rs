@@ -0,0 +1,198 @@
+use std::fmt::Formatter;
+
+use crate::board::{Board, Viewport};
+use crate::game::{CellState, Game, GameBoardOld};
+use crate::hashlife::HashLife;
+use crate::packed::PackedGame;
+use crate::rule::Rule;
+use crate::soup::{clock_seed, random_pattern, Random, Symmetry};
+use crate::sparse::SparseGame;
+use crate::topology::Topology;
+
+/// An engine to check, w/ a way to make an empty (width, height) board of it
+#[derive(Clone, Copy)]
+pub struct Engine {
+    pub name: &'static str,
+    pub new: fn(usize, usize) -> Box<dyn Board>,
+}
+
+/// Every engine. The infinite ones ([SparseGame] & [HashLife]) are looked at through a
+/// [Viewport], they only run bounded soups (see [Verify::run])
+pub const ENGINES: [Engine; 5] = [
+    Engine {
+        name: "game",
+        new: |width, height| Box::new(Game::new(width, height)),
+    },
+    Engine {
+        name: "old",
+        new: |width, height| Box::new(GameBoardOld::new(width, height)),
+    },
+    Engine {
+        name: "packed",
+        new: |width, height| Box::new(PackedGame::new(width, height)),
+    },
+    Engine {
+        name: "sparse",
+        new: |width, height| Box::new(Viewport::new(SparseGame::new(), (0, 0), width, height)),
+    },
+    Engine {
+        name: "hashlife",
+        new: |width, height| {
+            let life = HashLife::new(Rule::default()).expect("HashLife runs Life");
+            Box::new(Viewport::new(life, (0, 0), width, height))
+        },
+    },
+];
+
+/// Where 2 engines first disagreed, & the soup that made them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub engines: (&'static str, &'static str),
+    /// How many generations the soup had been run for
+    pub generation: usize,
+    pub cell: (usize, usize),
+    /// The cell on each engine, None if the engine said it was off the board
+    pub states: (Option<CellState>, Option<CellState>),
+    /// The (width, height) of the boards, the soup & any margin around it
+    pub size: (usize, usize),
+    pub seed: u64,
+    pub rule: Rule,
+    pub topology: Topology,
+}
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let state = |s: Option<CellState>| s.map_or("off the board".to_string(), |s| s.to_string());
+        writeln!(
+            f,
+            "\"{}\" & \"{}\" disagree at {:?} after {} generations: {} vs {}",
+            self.engines.0,
+            self.engines.1,
+            self.cell,
+            self.generation,
+            state(self.states.0),
+            state(self.states.1)
+        )?;
+        write!(
+            f,
+            "(a {} by {} soup w/ seed {}, running {} w/ {} edges)",
+            self.size.0, self.size.1, self.seed, self.rule, self.topology
+        )
+    }
+}
+
+/// Runs engines side by side on random soups of random sizes (rarely square), checking
+/// they agree on every cell of every generation. Each soup gets a random topology & one
+/// of the rules, engines that can't run the rule or topology sit that soup out
+#[derive(Debug, Clone)]
+pub struct Verify {
+    pub soups: usize,
+    pub generations: usize,
+    /// The biggest (width, height) a soup can be
+    pub max_size: (usize, usize),
+    /// Picks the size, topology, rule & seed of every soup
+    pub seed: u64,
+    pub rules: Vec<Rule>,
+}
+impl Verify {
+    /// 100 soups of up to 40 by 40 cells, run for 30 generations of Life, HighLife,
+    /// rules where lone cells survive (S0) or nothing does (B-only), & rules only some
+    /// engines can run
+    pub fn new() -> Self {
+        let rules = [
+            "B3/S23", "B36/S23", "B3/S023", "B2/S", "B2/S/C3", "B2-a/S12",
+        ]
+        .map(|r| Rule::parse(r).expect("the rules parse"));
+        Verify {
+            soups: 100,
+            generations: 30,
+            max_size: (40, 40),
+            seed: clock_seed(),
+            rules: rules.to_vec(),
+        }
+    }
+    /// Checks the engines agree, returning how many soups were checked (soups w/ fewer
+    /// than 2 engines that can run them are skipped) or where they first disagreed
+    ///
+    /// Infinite engines let patterns past the edges, so when one is checked bounded
+    /// soups get a dead margin around them, too wide for anything to reach the edge
+    pub fn run(&self, engines: &[Engine]) -> Result<usize, Box<Divergence>> {
+        let mut random = Random::new(self.seed);
+        let mut checked = 0;
+        let infinite = engines
+            .iter()
+            .any(|e| (e.new)(1, 1).topology() == Topology::Unbounded);
+        for _ in 0..self.soups {
+            let soup_width = 1 + (random.next_u64() as usize) % self.max_size.0.max(1);
+            let soup_height = 1 + (random.next_u64() as usize) % self.max_size.1.max(1);
+            let topology = Topology::ALL[(random.next_u64() as usize) % Topology::ALL.len()];
+            let rule = self.rules[(random.next_u64() as usize) % self.rules.len().max(1)];
+            let seed = random.next_u64();
+            let soup = random_pattern((soup_width, soup_height), 0.5, seed, Symmetry::C1)
+                .expect("the density & symmetry always work");
+            // Nothing moves faster than the radius of the neighborhood each generation
+            let margin = match topology {
+                Topology::Bounded if infinite => {
+                    self.generations * rule.neighborhood().radius() as usize
+                }
+                _ => 0,
+            };
+            let (width, height) = (soup_width + 2 * margin, soup_height + 2 * margin);
+
+            let mut boards: Vec<(&'static str, Box<dyn Board>)> = Vec::new();
+            for engine in engines {
+                let mut board = (engine.new)(width, height);
+                if board.set_topology(topology).is_err() || board.set_rule(rule).is_err() {
+                    continue;
+                }
+                // Cell by cell, so setting cells gets checked too
+                for y in 0..soup_height {
+                    for x in 0..soup_width {
+                        board.set(x + margin, y + margin, soup.get(x, y));
+                    }
+                }
+                boards.push((engine.name, board));
+            }
+            if boards.len() < 2 {
+                continue;
+            }
+
+            let divergence = |generation, cell, engines, states| Divergence {
+                engines,
+                generation,
+                cell,
+                states,
+                size: (width, height),
+                seed,
+                rule,
+                topology,
+            };
+            for generation in 0..=self.generations {
+                let (first, rest) = boards.split_first().unwrap();
+                for (name, board) in rest {
+                    for y in 0..height {
+                        for x in 0..width {
+                            let states = (first.1.get(x, y), board.get(x, y));
+                            if states.0 != states.1 {
+                                let engines = (first.0, *name);
+                                return Err(Box::new(divergence(
+                                    generation,
+                                    (x, y),
+                                    engines,
+                                    states,
+                                )));
+                            }
+                        }
+                    }
+                }
+                boards.iter_mut().for_each(|(_, board)| board.step(1));
+            }
+            checked += 1;
+        }
+        Ok(checked)
+    }
+}
+impl Default for Verify {
+    fn default() -> Self {
+        Verify::new()
+    }
+}